@@ -1,32 +1,19 @@
 #!/bin/bash
 
-# Paths (update these to match your container's directory structure)
-DBT_PROJECT_DIR="/backend/dbt_project"
-ENRICH_SCRIPT_PATH="/backend/scripts/enrich_manifest.py"
+# Refreshes every configured project through the backend's `refresh`
+# subcommand, so the cron job honours the same catalog.toml, CATALOG_* env
+# vars, profiles and targets as the server.
+BACKEND_DIR="${CATALOG_BACKEND_DIR:-/backend}"
 
-echo "Generating DBT documentation and model list..."
-cd "$DBT_PROJECT_DIR" || {
-  echo "Failed to navigate to dbt project directory: $DBT_PROJECT_DIR"
+cd "$BACKEND_DIR" || {
+  echo "Failed to navigate to backend directory: $BACKEND_DIR"
   exit 1
 }
 
-# Run DBT commands
-dbt docs generate
+echo "Refreshing the catalog cache..."
+./data_catalog_backend refresh "$@"
 if [ $? -ne 0 ]; then
-  echo "Error: Failed to generate dbt docs."
-  exit 1
-fi
-
-dbt ls --output json > target/models.json
-if [ $? -ne 0 ]; then
-  echo "Error: Failed to list dbt models."
-  exit 1
-fi
-
-echo "Enriching manifest..."
-python3 "$ENRICH_SCRIPT_PATH"
-if [ $? -ne 0 ]; then
-  echo "Error: Failed to enrich manifest."
+  echo "Error: Failed to refresh the cache."
   exit 1
 fi
 
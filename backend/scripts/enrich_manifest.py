@@ -1,11 +1,16 @@
 import json
 import os
+from datetime import datetime, timezone
 
-# DEV notes: unhardcode this to work in docker container
-# Paths to manifest.json and catalog.json
-manifest_path = "/backend/dbt_project/target/manifest.json"
-catalog_path = "/backend/dbt_project/target/catalog.json"
-enriched_manifest_path = "/backend/cache/enriched_manifest.json"
+# Paths to manifest.json and catalog.json (overridable to match the backend config)
+target_dir = os.environ.get("CATALOG_TARGET_DIR", "/backend/dbt_project/target")
+manifest_path = os.path.join(target_dir, "manifest.json")
+catalog_path = os.path.join(target_dir, "catalog.json")
+enriched_manifest_path = os.environ.get("CATALOG_MANIFEST_PATH", "/backend/cache/enriched_manifest.json")
+# Column snapshot of the previous refresh and the drift event log, next to the enriched manifest
+manifest_stem = os.path.splitext(enriched_manifest_path)[0]
+snapshot_path = os.environ.get("CATALOG_SNAPSHOT_PATH", manifest_stem + ".snapshot.json")
+drift_path = os.environ.get("CATALOG_DRIFT_PATH", manifest_stem + ".drift.jsonl")
 
 # Ensure the output directory exists
 output_dir = os.path.dirname(enriched_manifest_path)
@@ -18,10 +23,121 @@ with open(manifest_path) as manifest_file:
 with open(catalog_path) as catalog_file:
     catalog = json.load(catalog_file)
 
-# Enrich manifest with catalog columns
-for node_id, node in manifest.get('nodes', {}).items():
-    if node_id in catalog.get('nodes', {}):
-        node['columns'] = catalog['nodes'][node_id].get('columns', {})
+# YAML column properties kept when the warehouse columns replace the declared ones
+DECLARED_COLUMN_FIELDS = ('description', 'meta', 'tags', 'data_type', 'constraints')
+
+
+def merge_columns(declared, catalog_columns):
+    """Catalog columns (type, index, comment) carrying the YAML properties of the
+    matching declared column; declared-only columns are kept as they are."""
+    declared_by_name = {name.lower(): column for name, column in declared.items()}
+    merged = {}
+    for name, column in catalog_columns.items():
+        column = dict(column)
+        yaml_column = declared_by_name.pop(name.lower(), {})
+        for field in DECLARED_COLUMN_FIELDS:
+            if field in yaml_column:
+                column[field] = yaml_column[field]
+        merged[name] = column
+    for column in declared_by_name.values():
+        merged[column.get('name')] = column
+    return merged
+
+
+def column_snapshot(manifest, catalog):
+    """Per node: warehouse column types and YAML-declared data types, keyed by lowercased name."""
+    snapshot = {}
+    for section in ('nodes', 'sources'):
+        catalog_section = catalog.get(section, {})
+        for node_id, node in manifest.get(section, {}).items():
+            if node_id not in catalog_section and not node.get('columns'):
+                continue
+            snapshot[node_id] = {
+                'name': node.get('name'),
+                'in_catalog': node_id in catalog_section,
+                'catalog': {
+                    name.lower(): column.get('type')
+                    for name, column in catalog_section.get(node_id, {}).get('columns', {}).items()
+                },
+                'declared': {
+                    name.lower(): column.get('data_type')
+                    for name, column in node.get('columns', {}).items()
+                },
+            }
+    return snapshot
+
+
+def declared_issues(entry):
+    """Mismatches between the YAML-declared columns and the warehouse, as (kind, column) -> event fields."""
+    issues = {}
+    if not entry['in_catalog'] or not entry['declared']:
+        return issues
+    catalog_columns, declared = entry['catalog'], entry['declared']
+    for column, data_type in declared.items():
+        if column not in catalog_columns:
+            issues[('declared_missing', column)] = {'declared_type': data_type}
+        elif data_type and catalog_columns[column] and data_type.lower() != catalog_columns[column].lower():
+            issues[('declared_type_mismatch', column)] = {
+                'declared_type': data_type, 'current_type': catalog_columns[column],
+            }
+    for column, column_type in catalog_columns.items():
+        if column not in declared:
+            issues[('undocumented', column)] = {'current_type': column_type}
+    return issues
+
+
+def detect_drift(previous, current):
+    """Drift events between two column snapshots. Warehouse changes are only
+    reported against a previous snapshot; declared-vs-warehouse mismatches are
+    reported when they first appear."""
+    detected_at = datetime.now(timezone.utc).isoformat()
+    events = []
+
+    def event(node_id, column, kind, **fields):
+        events.append({
+            'detected_at': detected_at, 'unique_id': node_id, 'name': current[node_id]['name'],
+            'column': column, 'kind': kind, **fields,
+        })
+
+    for node_id, entry in current.items():
+        before = previous.get(node_id)
+        if before and before['in_catalog'] and entry['in_catalog']:
+            old_columns, new_columns = before['catalog'], entry['catalog']
+            for column in sorted(new_columns.keys() - old_columns.keys()):
+                event(node_id, column, 'added', current_type=new_columns[column])
+            for column in sorted(old_columns.keys() - new_columns.keys()):
+                event(node_id, column, 'removed', previous_type=old_columns[column])
+            for column in sorted(new_columns.keys() & old_columns.keys()):
+                if old_columns[column] != new_columns[column]:
+                    event(node_id, column, 'retyped', previous_type=old_columns[column], current_type=new_columns[column])
+
+        known_issues = declared_issues(before) if before else {}
+        for (kind, column), fields in sorted(declared_issues(entry).items()):
+            if (kind, column) not in known_issues:
+                event(node_id, column, kind, **fields)
+    return events
+
+
+# Record schema drift since the previous refresh
+previous_snapshot = {}
+if os.path.exists(snapshot_path):
+    with open(snapshot_path) as snapshot_file:
+        previous_snapshot = json.load(snapshot_file)
+current_snapshot = column_snapshot(manifest, catalog)
+drift_events = detect_drift(previous_snapshot, current_snapshot)
+with open(drift_path, 'a') as drift_file:
+    for drift_event in drift_events:
+        drift_file.write(json.dumps(drift_event) + "\n")
+with open(snapshot_path, 'w') as snapshot_file:
+    json.dump(current_snapshot, snapshot_file)
+print(f"Recorded {len(drift_events)} drift events in {os.path.abspath(drift_path)}")
+
+# Enrich manifest (and sources) with catalog columns
+for section in ('nodes', 'sources'):
+    catalog_section = catalog.get(section, {})
+    for node_id, node in manifest.get(section, {}).items():
+        if node_id in catalog_section:
+            node['columns'] = merge_columns(node.get('columns', {}), catalog_section[node_id].get('columns', {}))
 
 # Save enriched manifest
 with open(enriched_manifest_path, 'w') as output_file:
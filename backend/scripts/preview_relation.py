@@ -0,0 +1,37 @@
+import argparse
+import json
+
+import duckdb
+
+# Print the first rows of a relation as JSON for the backend's /models/:id/preview.
+parser = argparse.ArgumentParser(description="Preview a DuckDB relation")
+parser.add_argument("--database", required=True)
+parser.add_argument("--relation", required=True)
+parser.add_argument("--limit", type=int, required=True)
+parser.add_argument("--max-bytes", type=int, required=True)
+args = parser.parse_args()
+
+# Read-only, and no access to files or URLs outside the database
+connection = duckdb.connect(args.database, read_only=True, config={"enable_external_access": False})
+
+# The relation name comes from the manifest, not from the request
+cursor = connection.execute(f"SELECT * FROM {args.relation} LIMIT {args.limit + 1}")
+columns = [{"name": column[0], "type": str(column[1])} for column in cursor.description]
+
+rows = []
+used_bytes = 0
+truncated = False
+for row in cursor.fetchall():
+    if len(rows) == args.limit:
+        truncated = True
+        break
+    encoded = json.dumps(list(row), default=str)
+    if used_bytes + len(encoded) > args.max_bytes:
+        truncated = True
+        break
+    used_bytes += len(encoded)
+    rows.append(json.loads(encoded))
+
+connection.close()
+
+print(json.dumps({"columns": columns, "rows": rows, "truncated": truncated}))
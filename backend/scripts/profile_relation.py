@@ -0,0 +1,73 @@
+import argparse
+import json
+
+import duckdb
+
+# Print column statistics of a relation as JSON for the backend's profiling job.
+parser = argparse.ArgumentParser(description="Profile the columns of a DuckDB relation")
+parser.add_argument("--database", required=True)
+parser.add_argument("--relation", required=True)
+parser.add_argument("--top-k", type=int, default=5)
+args = parser.parse_args()
+
+NUMERIC_PREFIXES = ("TINYINT", "SMALLINT", "INTEGER", "BIGINT", "HUGEINT", "UTINYINT", "USMALLINT",
+                    "UINTEGER", "UBIGINT", "UHUGEINT", "FLOAT", "REAL", "DOUBLE", "DECIMAL", "NUMERIC")
+STRING_PREFIXES = ("VARCHAR", "TEXT", "STRING", "CHAR", "BPCHAR")
+
+
+def quote(identifier):
+    return '"' + identifier.replace('"', '""') + '"'
+
+
+def scalar(value):
+    """JSON friendly value: numbers stay numbers, everything else becomes text."""
+    if value is None or isinstance(value, (bool, int, float)):
+        return value
+    return str(value)
+
+
+connection = duckdb.connect(args.database, read_only=True, config={"enable_external_access": False})
+relation = args.relation  # from the manifest, not from a request
+
+row_count = connection.execute(f"SELECT count(*) FROM {relation}").fetchone()[0]
+described = connection.execute(f"DESCRIBE SELECT * FROM {relation}").fetchall()
+
+columns = {}
+for name, column_type, *_ in described:
+    column = quote(name)
+    upper_type = str(column_type).upper()
+    null_count, distinct_count, min_value, max_value = connection.execute(
+        f"SELECT count(*) - count({column}), count(DISTINCT {column}), min({column})::VARCHAR, max({column})::VARCHAR "
+        f"FROM {relation}"
+    ).fetchone()
+    stats = {
+        "type": str(column_type),
+        "null_count": null_count,
+        "distinct_count": distinct_count,
+        "min": min_value,
+        "max": max_value,
+    }
+
+    if upper_type.startswith(NUMERIC_PREFIXES):
+        mean, stddev = connection.execute(
+            f"SELECT avg({column})::DOUBLE, stddev_samp({column})::DOUBLE FROM {relation}"
+        ).fetchone()
+        stats.update({"mean": mean, "stddev": stddev})
+
+    if upper_type.startswith(STRING_PREFIXES):
+        min_length, max_length, avg_length = connection.execute(
+            f"SELECT min(length({column})), max(length({column})), avg(length({column}))::DOUBLE FROM {relation}"
+        ).fetchone()
+        stats.update({"min_length": min_length, "max_length": max_length, "avg_length": avg_length})
+
+    top_values = connection.execute(
+        f"SELECT {column}, count(*) AS n FROM {relation} WHERE {column} IS NOT NULL "
+        f"GROUP BY 1 ORDER BY n DESC, 1 LIMIT {args.top_k}"
+    ).fetchall()
+    stats["top_values"] = [{"value": scalar(value), "count": count} for value, count in top_values]
+
+    columns[name] = stats
+
+connection.close()
+
+print(json.dumps({"row_count": row_count, "columns": columns}))
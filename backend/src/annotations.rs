@@ -0,0 +1,297 @@
+use axum::{extract::Path, http::StatusCode, Extension, Json};
+use log::error;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::collections::{HashMap, HashSet};
+
+use crate::auth::AuthUser;
+use crate::config::ProjectConfig;
+use crate::dbt::{find_model, ModelPath};
+use crate::glossary;
+use crate::projects::{load_manifest, Project};
+use crate::rbac::Access;
+use crate::store;
+
+/// Longest accepted annotation body, in bytes.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// A user-written note on a model or column, kept in the store next to (not
+/// in) the dbt description.
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct Annotation {
+    /// Markdown.
+    pub body: String,
+    /// Names of the glossary terms the note links to.
+    pub glossary_terms: Vec<String>,
+    pub verified: bool,
+    pub verified_by: Option<String>,
+    pub verified_at: Option<String>,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AnnotationUpdate {
+    /// Markdown, up to 64 KiB.
+    body: String,
+    #[serde(default)]
+    glossary_terms: Vec<String>,
+    /// Needs an authenticated caller.
+    #[serde(default)]
+    verified: bool,
+    /// Only used without authentication; otherwise the caller is the author.
+    author: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct ColumnPath {
+    /// Model name or unique_id.
+    id: String,
+    /// Column name.
+    col: String,
+}
+
+/// Annotations of a model keyed by lowercased column name; the model's own
+/// annotation is under the empty key.
+pub fn load_annotations(project: &ProjectConfig, unique_id: &str) -> HashMap<String, Annotation> {
+    store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT column_name, body, glossary_terms, verified, verified_by, verified_at, author, created_at, updated_at
+             FROM annotations WHERE project = ?1 AND unique_id = ?2",
+        )?;
+        let rows = stmt.query_map(params![project.name, unique_id], |row| {
+            let glossary_terms: String = row.get(2)?;
+            Ok((
+                row.get(0)?,
+                Annotation {
+                    body: row.get(1)?,
+                    glossary_terms: serde_json::from_str(&glossary_terms).unwrap_or_default(),
+                    verified: row.get(3)?,
+                    verified_by: row.get(4)?,
+                    verified_at: row.get(5)?,
+                    author: row.get(6)?,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                },
+            ))
+        })?;
+        rows.collect()
+    })
+    .unwrap_or_else(|e| {
+        error!("Failed to load annotations of {}: {}", unique_id, e);
+        HashMap::new()
+    })
+}
+
+/// Create or replace an annotation. Verification keeps its original
+/// verifier and time until the badge is removed.
+fn save_annotation(
+    project: &ProjectConfig,
+    unique_id: &str,
+    column: &str,
+    update: &AnnotationUpdate,
+) -> Result<Annotation, String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut seen = HashSet::new();
+    let glossary_terms: Vec<String> = update
+        .glossary_terms
+        .iter()
+        .map(|term| term.trim().to_string())
+        .filter(|term| !term.is_empty() && seen.insert(term.clone()))
+        .collect();
+    let verified_by = update.verified.then(|| update.author.clone()).flatten();
+    let verified_at = update.verified.then(|| now.clone());
+
+    store::with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO annotations
+             (project, unique_id, column_name, body, glossary_terms, verified, verified_by, verified_at, author, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)
+             ON CONFLICT (project, unique_id, column_name) DO UPDATE SET
+                 body = excluded.body,
+                 glossary_terms = excluded.glossary_terms,
+                 verified = excluded.verified,
+                 verified_by = CASE WHEN annotations.verified AND excluded.verified THEN annotations.verified_by ELSE excluded.verified_by END,
+                 verified_at = CASE WHEN annotations.verified AND excluded.verified THEN annotations.verified_at ELSE excluded.verified_at END,
+                 author = excluded.author,
+                 updated_at = excluded.updated_at",
+            params![
+                project.name,
+                unique_id,
+                column,
+                update.body,
+                serde_json::to_string(&glossary_terms).unwrap_or_default(),
+                update.verified,
+                verified_by,
+                verified_at,
+                update.author,
+                now,
+            ],
+        )
+    })?;
+
+    load_annotations(project, unique_id)
+        .remove(column)
+        .ok_or_else(|| format!("Annotation of {} was not saved", unique_id))
+}
+
+/// unique_id of the model and, for column routes, the lowercased column name.
+fn resolve(
+    project: &ProjectConfig,
+    access: &Access,
+    id: &str,
+    column: Option<&str>,
+) -> Result<(String, String), (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let model = find_model(&manifest, id).filter(|model| access.can_see(model)).ok_or_else(|| (StatusCode::NOT_FOUND, format!("Model not found: {}", id)))?;
+    let unique_id = model.get("unique_id").and_then(|u| u.as_str()).unwrap_or(id).to_string();
+
+    let Some(column) = column else {
+        return Ok((unique_id, String::new()));
+    };
+    let known = model
+        .get("columns")
+        .and_then(|c| c.as_object())
+        .is_some_and(|cols| cols.keys().any(|name| name.eq_ignore_ascii_case(column)));
+    let hidden = access.hidden_columns().get(&unique_id).is_some_and(|hidden| hidden.contains(&column.to_lowercase()));
+    if !known || hidden {
+        return Err((StatusCode::NOT_FOUND, format!("Column {} not found on {}", column, id)));
+    }
+    Ok((unique_id, column.to_lowercase()))
+}
+
+/// Check an update and settle who wrote it. Only authenticated callers may
+/// verify, since the author of an anonymous update is whatever it claims.
+/// Glossary terms must exist and are stored under their canonical names.
+fn validate(update: &mut AnnotationUpdate, user: Option<Extension<AuthUser>>) -> Result<(), (StatusCode, String)> {
+    if update.body.len() > MAX_BODY_BYTES {
+        return Err((StatusCode::PAYLOAD_TOO_LARGE, format!("Annotation body is limited to {} bytes", MAX_BODY_BYTES)));
+    }
+    match user {
+        Some(Extension(user)) => update.author = Some(user.name),
+        None if update.verified => {
+            return Err((StatusCode::FORBIDDEN, "Verifying an annotation requires authentication".to_string()));
+        }
+        None => {}
+    }
+
+    if update.glossary_terms.iter().any(|term| !term.trim().is_empty()) {
+        let terms = glossary::load_terms().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        for name in update.glossary_terms.iter_mut().filter(|name| !name.trim().is_empty()) {
+            let term = terms
+                .iter()
+                .find(|term| term.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| (StatusCode::BAD_REQUEST, format!("Unknown glossary term: {}", name.trim())))?;
+            *name = term.name.clone();
+        }
+    }
+    Ok(())
+}
+
+fn annotation_of(project: &ProjectConfig, unique_id: &str, column: &str, id: &str) -> Result<Json<Annotation>, (StatusCode, String)> {
+    load_annotations(project, unique_id)
+        .remove(column)
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("{} has no annotation", id)))
+}
+
+/// The annotation of a model.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/annotations",
+    tag = "annotations",
+    params(ModelPath),
+    responses(
+        (status = 200, body = Annotation),
+        (status = 404, description = "Unknown or hidden model or column, or no annotation yet", body = String),
+    )
+)]
+pub async fn get_model_annotation(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+) -> Result<Json<Annotation>, (StatusCode, String)> {
+    let (unique_id, column) = resolve(project, &access, &id, None)?;
+    annotation_of(project, &unique_id, &column, &id)
+}
+
+/// Create or replace the annotation of a model.
+#[utoipa::path(
+    put,
+    path = "/models/{id}/annotations",
+    tag = "annotations",
+    params(ModelPath),
+    request_body = AnnotationUpdate,
+    responses(
+        (status = 200, body = Annotation),
+        (status = 400, description = "Unknown glossary term", body = String),
+        (status = 403, description = "Verification without authentication", body = String),
+        (status = 413, description = "Body too large", body = String),
+        (status = 404, description = "Unknown or hidden model or column, or no annotation yet", body = String),
+    )
+)]
+pub async fn put_model_annotation(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+    user: Option<Extension<AuthUser>>,
+    Json(mut update): Json<AnnotationUpdate>,
+) -> Result<Json<Annotation>, (StatusCode, String)> {
+    validate(&mut update, user)?;
+    let (unique_id, column) = resolve(project, &access, &id, None)?;
+    save_annotation(project, &unique_id, &column, &update)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// The annotation of a column.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/columns/{col}/annotations",
+    tag = "annotations",
+    params(ColumnPath),
+    responses(
+        (status = 200, body = Annotation),
+        (status = 404, description = "Unknown or hidden model or column, or no annotation yet", body = String),
+    )
+)]
+pub async fn get_column_annotation(
+    Project(project): Project,
+    access: Access,
+    Path(ColumnPath { id, col }): Path<ColumnPath>,
+) -> Result<Json<Annotation>, (StatusCode, String)> {
+    let (unique_id, column) = resolve(project, &access, &id, Some(&col))?;
+    annotation_of(project, &unique_id, &column, &format!("{}.{}", id, col))
+}
+
+/// Create or replace the annotation of a column.
+#[utoipa::path(
+    put,
+    path = "/models/{id}/columns/{col}/annotations",
+    tag = "annotations",
+    params(ColumnPath),
+    request_body = AnnotationUpdate,
+    responses(
+        (status = 200, body = Annotation),
+        (status = 400, description = "Unknown glossary term", body = String),
+        (status = 403, description = "Verification without authentication", body = String),
+        (status = 413, description = "Body too large", body = String),
+        (status = 404, description = "Unknown or hidden model or column, or no annotation yet", body = String),
+    )
+)]
+pub async fn put_column_annotation(
+    Project(project): Project,
+    access: Access,
+    Path(ColumnPath { id, col }): Path<ColumnPath>,
+    user: Option<Extension<AuthUser>>,
+    Json(mut update): Json<AnnotationUpdate>,
+) -> Result<Json<Annotation>, (StatusCode, String)> {
+    validate(&mut update, user)?;
+    let (unique_id, column) = resolve(project, &access, &id, Some(&col))?;
+    save_annotation(project, &unique_id, &column, &update)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
@@ -0,0 +1,216 @@
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, MatchedPath, Path, Query},
+    http::{header, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::error;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::collections::HashMap;
+
+use crate::auth::{self, AuthUser};
+use crate::config;
+use crate::store;
+
+const DEFAULT_LIMIT: usize = 1000;
+
+/// One audited request.
+#[derive(Serialize, ToSchema)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub occurred_at: String,
+    /// Authenticated caller; `None` for anonymous or rejected requests.
+    pub user: Option<String>,
+    pub method: String,
+    /// Route pattern, e.g. `/models/:id/annotations`.
+    pub route: String,
+    pub project: Option<String>,
+    pub entity_id: Option<String>,
+    /// `<verb> <resource>`, e.g. `update annotations` or `read preview`.
+    pub action: String,
+    pub status: u16,
+    /// `success`, `denied` or `failed`.
+    pub outcome: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditParams {
+    /// RFC 3339; events at or after it.
+    since: Option<String>,
+    /// RFC 3339; events before it.
+    until: Option<String>,
+    user: Option<String>,
+    project: Option<String>,
+    action: Option<String>,
+    limit: Option<usize>,
+    /// `json` (default) or `jsonl` for a JSON lines export.
+    format: Option<String>,
+}
+
+/// Fixed-width UTC timestamps so stored values compare as strings.
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn outcome(status: u16) -> &'static str {
+    match status {
+        401 | 403 => "denied",
+        400.. => "failed",
+        _ => "success",
+    }
+}
+
+fn action(method: &Method, route: &str) -> String {
+    let verb = match *method {
+        Method::GET | Method::HEAD => "read",
+        Method::POST => "create",
+        Method::PUT | Method::PATCH => "update",
+        Method::DELETE => "delete",
+        _ => method.as_str(),
+    };
+    let resource = route.rsplit('/').find(|segment| !segment.is_empty() && !segment.starts_with(':')).unwrap_or("/");
+    format!("{} {}", verb, resource)
+}
+
+/// Path parameters naming the entity a request acts on, joined with `/`.
+fn entity_id(params: &HashMap<String, String>) -> Option<String> {
+    let parts: Vec<&str> = ["id", "term", "col", "link_id"]
+        .iter()
+        .filter_map(|key| params.get(*key).map(String::as_str))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Record every mutating request and every data read (previews and
+/// profiles), including the ones authentication rejected.
+pub async fn record(req: Request<Body>, next: Next<Body>) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let group = auth::route_group(&method, &path);
+    if group != "write" && group != "data" {
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let route = parts.extensions.get::<MatchedPath>().map_or(path, |matched| matched.as_str().to_string());
+    let params = Path::<HashMap<String, String>>::from_request_parts(&mut parts, &())
+        .await
+        .map(|Path(params)| params)
+        .unwrap_or_default();
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    // The auth middleware hands the caller back on the response
+    let user = response.extensions().get::<AuthUser>().map(|user| user.name.clone());
+    // Un-prefixed project routes act on the default project; the glossary is shared
+    let project = match params.get("project") {
+        Some(project) => Some(project.clone()),
+        None if route.starts_with("/glossary") => None,
+        None => Some(config::get().default_project().name.clone()),
+    };
+    let status = response.status().as_u16();
+    let result = store::with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO audit_events (occurred_at, user, method, route, project, entity_id, action, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                timestamp(Utc::now()),
+                user,
+                method.as_str(),
+                route,
+                project,
+                entity_id(&params),
+                action(&method, &route),
+                status,
+            ],
+        )
+    });
+    if let Err(err) = result {
+        error!("Failed to record audit event for {} {}: {}", method, route, err);
+    }
+    response
+}
+
+fn parse_time(name: &str, value: &Option<String>) -> Result<Option<String>, (StatusCode, String)> {
+    value
+        .as_deref()
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|time| timestamp(time.with_timezone(&Utc)))
+                .map_err(|_| (StatusCode::BAD_REQUEST, format!("{} must be an RFC 3339 timestamp, got '{}'", name, value)))
+        })
+        .transpose()
+}
+
+/// Audit events, newest first, as JSON or (`format=jsonl`) JSON lines.
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "audit",
+    params(AuditParams),
+    responses(
+        (status = 200, description = "JSON array, or JSON lines with `format=jsonl`", body = Vec<AuditEvent>),
+        (status = 400, description = "Invalid timestamp or format", body = String),
+    )
+)]
+pub async fn get_audit(Query(params): Query<AuditParams>) -> Result<Response, (StatusCode, String)> {
+    let since = parse_time("since", &params.since)?;
+    let until = parse_time("until", &params.until)?;
+    let jsonl = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("jsonl") => true,
+        Some(other) => return Err((StatusCode::BAD_REQUEST, format!("format must be json or jsonl, got '{}'", other))),
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT) as i64;
+
+    let events = store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, occurred_at, user, method, route, project, entity_id, action, status FROM audit_events
+             WHERE (?1 IS NULL OR occurred_at >= ?1) AND (?2 IS NULL OR occurred_at < ?2)
+               AND (?3 IS NULL OR user = ?3) AND (?4 IS NULL OR project = ?4) AND (?5 IS NULL OR action = ?5)
+             ORDER BY id DESC LIMIT ?6",
+        )?;
+        let rows = stmt.query_map(
+            params![since, until, params.user, params.project, params.action, limit],
+            |row| {
+                let status: u16 = row.get(8)?;
+                Ok(AuditEvent {
+                    id: row.get(0)?,
+                    occurred_at: row.get(1)?,
+                    user: row.get(2)?,
+                    method: row.get(3)?,
+                    route: row.get(4)?,
+                    project: row.get(5)?,
+                    entity_id: row.get(6)?,
+                    action: row.get(7)?,
+                    status,
+                    outcome: outcome(status).to_string(),
+                })
+            },
+        )?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    if !jsonl {
+        return Ok(Json(events).into_response());
+    }
+    let body: String = events
+        .iter()
+        .filter_map(|event| serde_json::to_string(event).ok())
+        .map(|line| line + "\n")
+        .collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"audit.jsonl\""),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -0,0 +1,279 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use log::warn;
+use serde::Serialize;
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::config::{self, AuthConfig, JwtConfig};
+use crate::utils::read_file;
+
+/// Route groups that can be opened to anonymous callers with `auth.anonymous_groups`.
+pub const ROUTE_GROUPS: &[&str] = &["metadata", "data", "write", "admin"];
+
+static JWKS: OnceLock<JwkSet> = OnceLock::new();
+
+/// The caller, available to handlers as a request extension once authenticated.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthUser {
+    pub name: String,
+    pub roles: Vec<String>,
+    /// `token`, `basic` or `jwt`.
+    pub method: &'static str,
+}
+
+/// Load the JWKS file when JWT authentication is configured.
+pub fn init() -> Result<(), String> {
+    let Some(jwt) = &config::get().auth.jwt else {
+        return Ok(());
+    };
+    let content = read_file(&jwt.jwks_path.display().to_string())
+        .map_err(|e| format!("Failed to read JWKS {}: {}", jwt.jwks_path.display(), e))?;
+    let jwks: JwkSet = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid JWKS {}: {}", jwt.jwks_path.display(), e))?;
+    if jwks.keys.is_empty() {
+        return Err(format!("JWKS {} has no keys", jwt.jwks_path.display()));
+    }
+    for jwk in &jwks.keys {
+        key_algorithm(jwt, jwk).map_err(|e| format!("JWKS {}: {}", jwt.jwks_path.display(), e))?;
+    }
+    JWKS.set(jwks).map_err(|_| "auth::init called twice".to_string())
+}
+
+/// Which group a request belongs to, for `auth.anonymous_groups`.
+pub fn route_group(method: &Method, path: &str) -> &'static str {
+    // `/projects/:project/...` is grouped like the un-prefixed route
+    let path = match path.strip_prefix("/projects/") {
+        Some(rest) => rest.find('/').map_or("/", |i| &rest[i..]),
+        None => path,
+    };
+    if path == "/audit" {
+        "admin"
+    } else if path == "/graphql" {
+        // Queries only read metadata, whatever the method
+        "metadata"
+    } else if (path.starts_with("/models/") && (path.ends_with("/preview") || path.ends_with("/profile")))
+        || (path.starts_with("/seeds/") && path.ends_with("/preview"))
+    {
+        "data"
+    } else if matches!(*method, Method::GET | Method::HEAD) {
+        "metadata"
+    } else {
+        "write"
+    }
+}
+
+/// Constant-time comparison so token checks don't leak how much matched.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn basic_user(auth: &AuthConfig, credentials: &str) -> Result<AuthUser, String> {
+    let decoded = STANDARD
+        .decode(credentials.trim())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or("Malformed basic credentials")?;
+    let (username, password) = decoded.split_once(':').ok_or("Malformed basic credentials")?;
+    let user = auth.users.iter().find(|user| user.username == username);
+
+    // Unknown usernames are checked against another user's hash, so the
+    // response time doesn't tell which usernames exist
+    let hash = user.or(auth.users.first()).ok_or("Invalid username or password")?.password_hash.clone();
+    // bcrypt is deliberately slow, keep it off the async workers
+    let password = password.to_string();
+    let valid = tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash).unwrap_or(false))
+        .await
+        .unwrap_or(false);
+    let user = user.filter(|_| valid).ok_or("Invalid username or password")?;
+    Ok(AuthUser { name: user.username.clone(), roles: user.roles.clone(), method: "basic" })
+}
+
+/// The algorithm a key verifies: its own `alg`, else `auth.jwt.algorithm`.
+fn key_algorithm(jwt: &JwtConfig, jwk: &Jwk) -> Result<Algorithm, String> {
+    let name = match (&jwk.common.key_algorithm, &jwt.algorithm) {
+        (Some(alg), _) => alg.to_string(),
+        (None, Some(alg)) => alg.clone(),
+        (None, None) => return Err("a key has no \"alg\"; set auth.jwt.algorithm".to_string()),
+    };
+    Algorithm::from_str(&name).map_err(|_| format!("{} is not a JWT signing algorithm", name))
+}
+
+fn jwt_user(jwt: &JwtConfig, jwks: &JwkSet, token: &str) -> Result<AuthUser, String> {
+    let header = decode_header(token).map_err(|_| "Invalid token")?;
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or("Token is not signed by a configured key")?;
+    let key = DecodingKey::from_jwk(jwk).map_err(|e| format!("Unusable JWKS key: {}", e))?;
+
+    // The header is unverified, so the key decides the algorithm
+    let algorithm = key_algorithm(jwt, jwk)?;
+    if header.alg != algorithm {
+        return Err(format!("Token is signed with {:?}, but the key expects {:?}", header.alg, algorithm));
+    }
+    let mut validation = Validation::new(algorithm);
+    match &jwt.issuer {
+        Some(issuer) => validation.set_issuer(&[issuer]),
+        None => validation.iss = None,
+    }
+    match &jwt.audience {
+        Some(audience) => validation.set_audience(&[audience]),
+        None => validation.validate_aud = false,
+    }
+    let claims = decode::<Value>(token, &key, &validation)
+        .map_err(|e| format!("Invalid token: {}", e))?
+        .claims;
+
+    let name = claims
+        .get(&jwt.username_claim)
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| format!("Token has no '{}' claim", jwt.username_claim))?;
+    let roles = match claims.get(&jwt.roles_claim) {
+        Some(Value::Array(roles)) => roles.iter().filter_map(|r| r.as_str()).map(String::from).collect(),
+        Some(Value::String(roles)) => roles.split_whitespace().map(String::from).collect(),
+        _ => vec![],
+    };
+    Ok(AuthUser { name: name.to_string(), roles, method: "jwt" })
+}
+
+/// The caller identified by the `Authorization` header; `None` when it is absent.
+async fn identify(auth: &AuthConfig, headers: &HeaderMap) -> Result<Option<AuthUser>, String> {
+    let Some(value) = headers.get(header::AUTHORIZATION) else {
+        return Ok(None);
+    };
+    let value = value.to_str().map_err(|_| "Malformed Authorization header")?;
+    let (scheme, credentials) = value.split_once(' ').ok_or("Malformed Authorization header")?;
+
+    if scheme.eq_ignore_ascii_case("basic") && !auth.users.is_empty() {
+        return basic_user(auth, credentials).await.map(Some);
+    }
+    if scheme.eq_ignore_ascii_case("bearer") {
+        let credentials = credentials.trim();
+        if let Some(token) = auth.tokens.iter().find(|token| tokens_match(&token.token, credentials)) {
+            return Ok(Some(AuthUser { name: token.name.clone(), roles: token.roles.clone(), method: "token" }));
+        }
+        if let Some(jwt) = &auth.jwt {
+            let jwks = JWKS.get().ok_or("JWT authentication is not initialized")?;
+            return jwt_user(jwt, jwks, credentials).map(Some);
+        }
+        return Err("Invalid token".to_string());
+    }
+    Err(format!("Unsupported authorization scheme: {}", scheme))
+}
+
+fn unauthorized(auth: &AuthConfig, message: String) -> Response {
+    let challenge = if auth.users.is_empty() { "Bearer" } else { "Basic realm=\"catalog\", charset=\"UTF-8\"" };
+    (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, challenge)], message).into_response()
+}
+
+/// Authenticate every request when `[auth]` is configured. Invalid
+/// credentials are rejected even on anonymous route groups.
+pub async fn authenticate(mut req: Request<Body>, next: Next<Body>) -> Response {
+    let auth = &config::get().auth;
+    if !auth.enabled() {
+        return next.run(req).await;
+    }
+
+    let group = route_group(req.method(), req.uri().path());
+    match identify(auth, req.headers()).await {
+        Ok(Some(user)) => {
+            req.extensions_mut().insert(user.clone());
+            // Also on the response, for the audit log wrapped around this layer
+            let mut response = next.run(req).await;
+            response.extensions_mut().insert(user);
+            response
+        }
+        Ok(None) if auth.anonymous_groups.iter().any(|g| g == group) => next.run(req).await,
+        Ok(None) => unauthorized(auth, "Authentication required".to_string()),
+        Err(err) => {
+            warn!("Rejected {} {}: {}", req.method(), req.uri().path(), err);
+            unauthorized(auth, err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn jwks() -> JwkSet {
+        let k = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(SECRET);
+        serde_json::from_value(json!({"keys": [{"kty": "oct", "kid": "k1", "alg": "HS256", "k": k}]})).unwrap()
+    }
+
+    fn jwt_config() -> JwtConfig {
+        JwtConfig {
+            jwks_path: PathBuf::from("jwks.json"),
+            issuer: Some("https://idp.example.com/".to_string()),
+            audience: None,
+            username_claim: "sub".to_string(),
+            roles_claim: "roles".to_string(),
+            algorithm: None,
+        }
+    }
+
+    fn token(alg: Algorithm, claims: Value) -> String {
+        let header = Header { kid: Some("k1".to_string()), ..Header::new(alg) };
+        encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn claims(issuer: &str, expires_in: i64) -> Value {
+        let exp = chrono::Utc::now().timestamp() + expires_in;
+        json!({"sub": "ada", "iss": issuer, "exp": exp, "roles": "analyst finance"})
+    }
+
+    #[test]
+    fn jwt_user_accepts_a_valid_token() {
+        let user = jwt_user(&jwt_config(), &jwks(), &token(Algorithm::HS256, claims("https://idp.example.com/", 600))).unwrap();
+        assert_eq!(user.name, "ada");
+        assert_eq!(user.roles, ["analyst", "finance"]);
+        assert_eq!(user.method, "jwt");
+    }
+
+    #[test]
+    fn jwt_user_rejects_an_algorithm_other_than_the_keys() {
+        let err = jwt_user(&jwt_config(), &jwks(), &token(Algorithm::HS512, claims("https://idp.example.com/", 600))).unwrap_err();
+        assert!(err.contains("HS512"), "{}", err);
+    }
+
+    #[test]
+    fn jwt_user_rejects_an_expired_token() {
+        let err = jwt_user(&jwt_config(), &jwks(), &token(Algorithm::HS256, claims("https://idp.example.com/", -600))).unwrap_err();
+        assert!(err.contains("ExpiredSignature"), "{}", err);
+    }
+
+    #[test]
+    fn jwt_user_rejects_the_wrong_issuer() {
+        let err = jwt_user(&jwt_config(), &jwks(), &token(Algorithm::HS256, claims("https://evil.example.com/", 600))).unwrap_err();
+        assert!(err.contains("InvalidIssuer"), "{}", err);
+    }
+
+    #[test]
+    fn key_algorithm_falls_back_to_the_configured_one() {
+        let mut jwks = jwks();
+        jwks.keys[0].common.key_algorithm = None;
+        let mut config = jwt_config();
+        assert!(key_algorithm(&config, &jwks.keys[0]).is_err());
+        config.algorithm = Some("HS384".to_string());
+        assert_eq!(key_algorithm(&config, &jwks.keys[0]).unwrap(), Algorithm::HS384);
+    }
+}
@@ -0,0 +1,172 @@
+use axum::Json;
+use regex::Regex;
+use serde::Serialize;
+use utoipa::ToSchema;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::config;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, Project};
+use crate::rbac::Access;
+
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct Reason {
+    /// `meta`, `tag`, `rule` or `lineage`.
+    source: String,
+    detail: String,
+}
+
+#[derive(Serialize, Clone, Debug, Default, ToSchema)]
+pub struct ColumnClassification {
+    pub labels: BTreeSet<String>,
+    pub reasons: Vec<Reason>,
+}
+
+/// Classified columns of one node, keyed by lowercased column name.
+pub type NodeClassifications = BTreeMap<String, ColumnClassification>;
+
+#[derive(Serialize, ToSchema)]
+pub struct ClassifiedColumn {
+    unique_id: String,
+    name: String,
+    resource_type: String,
+    column: String,
+    #[serde(flatten)]
+    classification: ColumnClassification,
+}
+
+impl ColumnClassification {
+    fn add(&mut self, label: String, source: &str, detail: String) {
+        self.labels.insert(label);
+        self.reasons.push(Reason { source: source.to_string(), detail });
+    }
+}
+
+/// What a column's own metadata says: a set of labels, or an explicit opt-out.
+enum Declared {
+    Labels(ColumnClassification),
+    OptOut,
+}
+
+fn declared(column: &Value) -> Declared {
+    let settings = &config::get().classification;
+    let mut result = ColumnClassification::default();
+
+    if let Some(meta) = column.get("meta").and_then(|m| m.as_object()) {
+        for key in &settings.meta_keys {
+            match meta.get(key) {
+                Some(Value::Bool(false)) => return Declared::OptOut,
+                Some(Value::Bool(true)) => result.add("pii".to_string(), "meta", format!("{}: true", key)),
+                Some(Value::String(label)) if !label.is_empty() => {
+                    result.add(label.to_lowercase(), "meta", format!("{}: {}", key, label))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(tags) = column.get("tags").and_then(|t| t.as_array()) {
+        for tag in tags.iter().filter_map(|t| t.as_str()) {
+            if settings.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                result.add(tag.to_lowercase(), "tag", tag.to_string());
+            }
+        }
+    }
+
+    Declared::Labels(result)
+}
+
+/// Classify every column in the graph. Labels come from column meta and tags,
+/// then the configured name rules, then (when enabled) same-named columns of
+/// upstream nodes, walking the graph parents-first so labels flow all the way
+/// down. Without column-level lineage, matching on column name is the proxy
+/// for "derived from".
+pub fn classify(graph: &CatalogGraph) -> HashMap<String, NodeClassifications> {
+    let settings = &config::get().classification;
+    // Patterns were validated at startup
+    let rules: Vec<(&config::ClassificationRule, Regex)> = settings
+        .rules
+        .iter()
+        .filter_map(|rule| Regex::new(&rule.pattern).ok().map(|re| (rule, re)))
+        .collect();
+
+    let mut result: HashMap<String, NodeClassifications> = HashMap::new();
+    for unique_id in graph.topological_order() {
+        let Some(graph_node) = graph.nodes.get(&unique_id) else {
+            continue;
+        };
+        let Some(columns) = graph_node.node.get("columns").and_then(|c| c.as_object()) else {
+            continue;
+        };
+
+        let mut node_result = NodeClassifications::new();
+        for (key, column) in columns {
+            let name = column.get("name").and_then(|n| n.as_str()).unwrap_or(key);
+            let lowered = name.to_lowercase();
+
+            let mut classification = match declared(column) {
+                Declared::OptOut => continue,
+                Declared::Labels(classification) => classification,
+            };
+            for (rule, re) in &rules {
+                if re.is_match(name) {
+                    classification.add(rule.label.clone(), "rule", rule.name.clone());
+                }
+            }
+            if settings.propagate {
+                for parent in graph.parents(&unique_id) {
+                    if let Some(upstream) = result.get(parent).and_then(|cols| cols.get(&lowered)) {
+                        for label in &upstream.labels {
+                            classification.add(label.clone(), "lineage", format!("{}.{}", parent, lowered));
+                        }
+                    }
+                }
+            }
+
+            if !classification.labels.is_empty() {
+                node_result.insert(lowered, classification);
+            }
+        }
+        if !node_result.is_empty() {
+            result.insert(unique_id, node_result);
+        }
+    }
+    result
+}
+
+/// Every classified column of the project's models, seeds, snapshots and sources.
+#[utoipa::path(get, path = "/classification", tag = "governance", responses((status = 200, body = Vec<ClassifiedColumn>)))]
+pub async fn get_classification(Project(project): Project, access: Access) -> Json<Vec<ClassifiedColumn>> {
+    let graph = CatalogGraph::build(&load_all_manifests());
+    let classifications = classify(&graph);
+
+    let mut columns: Vec<ClassifiedColumn> = classifications
+        .into_iter()
+        .filter_map(|(unique_id, node_columns)| {
+            let graph_node = graph.nodes.get(&unique_id)?;
+            if graph_node.project != project.name || !access.can_see(&graph_node.node) {
+                return None;
+            }
+            let node = &graph_node.node;
+            let name = node.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string();
+            let resource_type = node.get("resource_type").and_then(|r| r.as_str()).unwrap_or_default().to_string();
+            Some(
+                node_columns
+                    .into_iter()
+                    .map(|(column, classification)| ClassifiedColumn {
+                        unique_id: unique_id.clone(),
+                        name: name.clone(),
+                        resource_type: resource_type.clone(),
+                        column,
+                        classification,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .collect();
+    columns.sort_by(|a, b| (&a.unique_id, &a.column).cmp(&(&b.unique_id, &b.column)));
+
+    Json(columns)
+}
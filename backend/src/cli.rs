@@ -0,0 +1,348 @@
+use clap::{Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, ProjectConfig};
+use crate::coverage;
+use crate::diff::diff_manifests;
+use crate::export::export_catalog;
+use crate::graph::CatalogGraph;
+use crate::lint::{self, Severity};
+use crate::projects::load_all_manifests;
+use crate::rbac::Access;
+use crate::refresh;
+use crate::search::search_catalog;
+use crate::site::export_site;
+use crate::store;
+
+/// Subcommands of `data_catalog_backend`; without one the server starts.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run the HTTP server (the default)
+    Serve,
+    /// Run `dbt docs generate` and rebuild the enriched manifest
+    Refresh {
+        /// Only refresh this project (default: all)
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Nodes upstream and/or downstream of a model (both when neither flag is given)
+    Lineage {
+        /// Model name or unique_id
+        model: String,
+        #[arg(long)]
+        upstream: bool,
+        #[arg(long)]
+        downstream: bool,
+        /// Maximum number of hops (default: unlimited)
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Project to resolve the model in (default: the default project)
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Search models, sources, metrics, columns and glossary terms
+    Search {
+        query: String,
+        #[arg(long)]
+        limit: Option<usize>,
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Changes between two manifest files (no config needed)
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Exit with status 1 when the manifests differ
+        #[arg(long)]
+        exit_code: bool,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Documentation and test coverage of the project's models
+    Coverage {
+        #[arg(long)]
+        project: Option<String>,
+        /// Fail when less than this percentage of models and columns is documented
+        #[arg(long)]
+        min_documented: Option<f64>,
+        /// Fail when less than this percentage of models is tested
+        #[arg(long)]
+        min_tested: Option<f64>,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Check the project against the lint rules; fails when any error is found
+    Lint {
+        #[arg(long)]
+        project: Option<String>,
+        /// YAML rules file (default: `lint.rules_path`, else the built-in rules)
+        #[arg(long)]
+        rules: Option<PathBuf>,
+        /// Also fail on warnings
+        #[arg(long)]
+        strict: bool,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Export the catalog as JSON, or as a static HTML site with --site
+    Export {
+        /// Only export this project (default: all)
+        #[arg(long)]
+        project: Option<String>,
+        /// Write to this file instead of stdout
+        #[arg(long, short, conflicts_with = "site")]
+        output: Option<PathBuf>,
+        /// Render a static site (HTML + JSON) into this directory
+        #[arg(long)]
+        site: Option<PathBuf>,
+        /// Only export what these RBAC roles may see (default: everything)
+        #[arg(long)]
+        role: Vec<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+}
+
+impl Command {
+    /// `diff` only compares the files it is given.
+    pub fn needs_config(&self) -> bool {
+        !matches!(self, Command::Diff { .. })
+    }
+}
+
+#[derive(Serialize)]
+struct LineageEntry {
+    unique_id: String,
+    name: String,
+    resource_type: String,
+    project: String,
+    /// `upstream` or `downstream`.
+    direction: &'static str,
+}
+
+fn project(name: &Option<String>) -> Result<&'static ProjectConfig, String> {
+    let config = config::get();
+    match name {
+        Some(name) => config.project(name).ok_or_else(|| format!("Unknown project: {}", name)),
+        None => Ok(config.default_project()),
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<Value, String> {
+    let data = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Left-aligned columns padded to their widest cell.
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| rows.iter().map(|row| row[i].chars().count()).chain([header.len()]).max().unwrap_or(0))
+        .collect();
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(headers.to_vec());
+    line(widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().iter().map(String::as_str).collect());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+fn or_dash(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+/// Run a subcommand other than `serve`. The error is printed before exiting non-zero.
+pub fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Serve => unreachable!("serve is handled by main"),
+        Command::Refresh { project: name } => {
+            store::init()?;
+            match name {
+                Some(_) => refresh::refresh_project(project(&name)?),
+                None => refresh::run_refresh(),
+            }
+        }
+        Command::Lineage { model, upstream, downstream, depth, project: name, format } => {
+            let project = project(&name)?;
+            let graph = CatalogGraph::build_lineage(&load_all_manifests());
+            let unique_id = graph
+                .find(&model, &project.name)
+                .ok_or_else(|| format!("Model not found in project '{}': {}", project.name, model))?;
+            let (upstream, downstream) = if upstream || downstream { (upstream, downstream) } else { (true, true) };
+
+            let order = graph.topological_order();
+            let mut entries = vec![];
+            let directions = [
+                (upstream, "upstream", depth.map_or_else(|| graph.ancestors(unique_id), |d| graph.ancestors_within(unique_id, d))),
+                (downstream, "downstream", depth.map_or_else(|| graph.descendants(unique_id), |d| graph.descendants_within(unique_id, d))),
+            ];
+            for (_, direction, ids) in directions.iter().filter(|(wanted, _, _)| *wanted) {
+                // Parents before children, as dbt would build them
+                for id in order.iter().filter(|id| *id != unique_id && ids.contains(*id)) {
+                    let graph_node = &graph.nodes[id];
+                    let field = |key: &str| graph_node.node.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                    entries.push(LineageEntry {
+                        name: field("name"),
+                        resource_type: field("resource_type"),
+                        project: graph_node.project.clone(),
+                        unique_id: id.clone(),
+                        direction,
+                    });
+                }
+            }
+
+            match format {
+                Format::Json => print_json(&entries),
+                Format::Table => {
+                    let rows: Vec<Vec<String>> = entries
+                        .into_iter()
+                        .map(|e| vec![e.direction.to_string(), e.resource_type, e.name, e.project, e.unique_id])
+                        .collect();
+                    print_table(&["DIRECTION", "TYPE", "NAME", "PROJECT", "UNIQUE_ID"], &rows);
+                    Ok(())
+                }
+            }
+        }
+        Command::Search { query, limit, project: name, format } => {
+            store::init()?;
+            let hits = search_catalog(project(&name)?, &Access::unrestricted(), &query, limit).map_err(|(_, err)| err)?;
+            match format {
+                Format::Json => print_json(&hits),
+                Format::Table => {
+                    let rows: Vec<Vec<String>> = hits
+                        .into_iter()
+                        .map(|hit| {
+                            vec![hit.score.to_string(), hit.kind, hit.name, or_dash(&hit.column), or_dash(&hit.unique_id)]
+                        })
+                        .collect();
+                    print_table(&["SCORE", "KIND", "NAME", "COLUMN", "UNIQUE_ID"], &rows);
+                    Ok(())
+                }
+            }
+        }
+        Command::Diff { old, new, exit_code, format } => {
+            let changes = diff_manifests(&read_manifest(&old)?, &read_manifest(&new)?);
+            let changed = !changes.is_empty();
+            match format {
+                Format::Json => print_json(&changes)?,
+                Format::Table => {
+                    let rows: Vec<Vec<String>> = changes
+                        .into_iter()
+                        .map(|c| vec![c.kind, c.resource_type, c.unique_id, or_dash(&c.column), or_dash(&c.before), or_dash(&c.after)])
+                        .collect();
+                    print_table(&["CHANGE", "TYPE", "UNIQUE_ID", "COLUMN", "BEFORE", "AFTER"], &rows);
+                }
+            }
+            if exit_code && changed {
+                return Err("Manifests differ".to_string());
+            }
+            Ok(())
+        }
+        Command::Coverage { project: name, min_documented, min_tested, format } => {
+            let project = project(&name)?;
+            let manifest = read_manifest(&project.manifest_path)?;
+            let models = coverage::model_coverage(&manifest);
+            let summary = coverage::summarize(&models);
+            match format {
+                Format::Json => print_json(&serde_json::json!({ "summary": summary, "models": models }))?,
+                Format::Table => {
+                    let rows: Vec<Vec<String>> = models
+                        .iter()
+                        .map(|m| {
+                            vec![
+                                m.name.clone(),
+                                if m.described { "yes" } else { "no" }.to_string(),
+                                format!("{}/{}", m.documented_columns, m.columns),
+                                format!("{}/{}", m.tested_columns, m.columns),
+                                m.tests.to_string(),
+                            ]
+                        })
+                        .collect();
+                    print_table(&["MODEL", "DESCRIBED", "DOCUMENTED COLUMNS", "TESTED COLUMNS", "TESTS"], &rows);
+                    println!();
+                    println!(
+                        "{} models, {} columns: {}% documented, {}% of models tested",
+                        summary.models, summary.columns, summary.documented_pct, summary.tested_pct
+                    );
+                }
+            }
+
+            if let Some(min) = min_documented.filter(|min| summary.documented_pct < *min) {
+                return Err(format!("Documentation coverage {}% is below {}%", summary.documented_pct, min));
+            }
+            if let Some(min) = min_tested.filter(|min| summary.tested_pct < *min) {
+                return Err(format!("Test coverage {}% is below {}%", summary.tested_pct, min));
+            }
+            Ok(())
+        }
+        Command::Lint { project: name, rules, strict, format } => {
+            let rules = match rules {
+                Some(path) => lint::parse_rules(&path)?,
+                None => lint::load_rules()?,
+            };
+            let report = lint::lint(project(&name)?, &Access::unrestricted(), &rules)?;
+            match format {
+                Format::Json => print_json(&report)?,
+                Format::Table => {
+                    let rows: Vec<Vec<String>> = report
+                        .violations
+                        .iter()
+                        .map(|v| {
+                            let severity = if v.severity == Severity::Error { "error" } else { "warning" };
+                            vec![severity.to_string(), v.rule.clone(), v.name.clone(), v.message.clone()]
+                        })
+                        .collect();
+                    print_table(&["SEVERITY", "RULE", "NAME", "MESSAGE"], &rows);
+                    println!();
+                    println!("{} errors, {} warnings", report.errors, report.warnings);
+                }
+            }
+            if report.errors > 0 || (strict && report.warnings > 0) {
+                return Err("Lint failed".to_string());
+            }
+            Ok(())
+        }
+        Command::Export { project: name, output, site, role } => {
+            let only = name.as_ref().map(|_| project(&name)).transpose()?;
+            let access = if role.is_empty() { Access::unrestricted() } else { Access::for_roles(&role)? };
+            if let Some(dir) = site {
+                // Model pages include annotations and glossary links from the store
+                store::init()?;
+                let pages = export_site(&dir, only, &access)?;
+                println!("Wrote {} pages to {}", pages, dir.display());
+                return Ok(());
+            }
+            let export = export_catalog(only, &access);
+            let json = serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize export: {}", e))?;
+            match output {
+                Some(path) => fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e)),
+                None => {
+                    println!("{}", json);
+                    Ok(())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,783 @@
+use clap::Parser;
+use log::{info, warn};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::cli::Command;
+use crate::utils::read_file;
+
+const DEFAULT_CONFIG_PATH: &str = "catalog.toml";
+
+static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+/// Command line flags. Every flag can also be set through the matching
+/// `CATALOG_*` environment variable; flags win over env vars, which win over
+/// values from the TOML config file.
+#[derive(Parser, Debug, Default)]
+#[command(name = "data_catalog_backend", version, about = "dbt data catalog backend")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Path to the TOML config file (defaults to ./catalog.toml when present)
+    #[arg(long, global = true, env = "CATALOG_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Address the HTTP server binds to, e.g. 0.0.0.0:3000
+    #[arg(long, global = true, env = "CATALOG_LISTEN_ADDR")]
+    pub listen_addr: Option<String>,
+
+    /// Allowed CORS origins (comma separated, `*` allows any origin)
+    #[arg(long, global = true, env = "CATALOG_CORS_ORIGINS", value_delimiter = ',')]
+    pub cors_origins: Option<Vec<String>>,
+
+    /// dbt project directory
+    #[arg(long, global = true, env = "CATALOG_DBT_PROJECT_DIR")]
+    pub dbt_project_dir: Option<PathBuf>,
+
+    /// dbt profiles directory (passed to dbt as --profiles-dir)
+    #[arg(long, global = true, env = "CATALOG_DBT_PROFILES_DIR")]
+    pub dbt_profiles_dir: Option<PathBuf>,
+
+    /// dbt profile name (passed to dbt as --profile)
+    #[arg(long, global = true, env = "CATALOG_DBT_PROFILE")]
+    pub dbt_profile: Option<String>,
+
+    /// dbt target name (passed to dbt as --target)
+    #[arg(long, global = true, env = "CATALOG_DBT_TARGET")]
+    pub dbt_target: Option<String>,
+
+    /// dbt target directory holding manifest.json / catalog.json
+    #[arg(long, global = true, env = "CATALOG_TARGET_DIR")]
+    pub target_dir: Option<PathBuf>,
+
+    /// Path of the enriched manifest served by the API
+    #[arg(long, global = true, env = "CATALOG_MANIFEST_PATH")]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Script that enriches manifest.json with catalog.json columns
+    #[arg(long, global = true, env = "CATALOG_ENRICH_SCRIPT")]
+    pub enrich_script: Option<PathBuf>,
+
+    /// Seconds between cache refreshes run by the backend (0 disables)
+    #[arg(long, global = true, env = "CATALOG_REFRESH_INTERVAL")]
+    pub refresh_interval: Option<u64>,
+
+    /// SQLite database holding catalog history and annotations
+    #[arg(long, global = true, env = "CATALOG_STORE_PATH")]
+    pub store_path: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub dbt: DbtConfig,
+    pub artifacts: ArtifactsConfig,
+    pub refresh: RefreshConfig,
+    pub governance: GovernanceConfig,
+    pub classification: ClassificationConfig,
+    pub preview: PreviewConfig,
+    pub profiling: ProfilingConfig,
+    pub store: StoreConfig,
+    pub auth: AuthConfig,
+    pub rbac: RbacConfig,
+    pub lint: LintConfig,
+    pub health: HealthConfig,
+    /// Extra `[[projects]]`. When any are listed they replace the single
+    /// project described by `[dbt]`/`[artifacts]`; the first one is the default.
+    pub projects: Vec<ProjectConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub listen_addr: String,
+    pub cors_origins: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DbtConfig {
+    /// Name used in `/projects/:project` routes, defaults to "default".
+    pub name: Option<String>,
+    pub project_dir: PathBuf,
+    pub profiles_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub target: Option<String>,
+    /// DuckDB file to read for previews; resolved from profiles.yml when unset.
+    pub duckdb_path: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArtifactsConfig {
+    /// Defaults to `<project_dir>/target` when unset.
+    pub target_dir: Option<PathBuf>,
+    pub manifest_path: PathBuf,
+    pub enrich_script: PathBuf,
+}
+
+/// One dbt project served by the catalog.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub name: String,
+    pub project_dir: PathBuf,
+    pub manifest_path: PathBuf,
+    #[serde(default)]
+    pub target_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub profiles_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub duckdb_path: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshConfig {
+    /// Seconds between refreshes; 0 leaves refreshing to the cron job.
+    pub interval_secs: u64,
+}
+
+/// Meta keys read into the first-class governance fields. Keys are tried in
+/// order; dotted keys (`governance.owner`) reach into nested meta objects.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GovernanceConfig {
+    pub owner_keys: Vec<String>,
+    pub team_keys: Vec<String>,
+    pub domain_keys: Vec<String>,
+    pub tier_keys: Vec<String>,
+    pub pii_keys: Vec<String>,
+    pub sla_keys: Vec<String>,
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect();
+        GovernanceConfig {
+            owner_keys: keys(&["owner", "owners"]),
+            team_keys: keys(&["team"]),
+            domain_keys: keys(&["domain"]),
+            tier_keys: keys(&["tier"]),
+            pii_keys: keys(&["pii", "contains_pii"]),
+            sla_keys: keys(&["sla"]),
+        }
+    }
+}
+
+/// How columns get tagged as PII/sensitive.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClassificationConfig {
+    /// Column tags that are classification labels themselves (e.g. `pii`).
+    pub tags: Vec<String>,
+    /// Column meta keys holding a label (`sensitivity: confidential`) or a
+    /// flag (`pii: true`); `false` opts the column out of rules and inheritance.
+    pub meta_keys: Vec<String>,
+    /// Regex rules matched against column names.
+    pub rules: Vec<ClassificationRule>,
+    /// Inherit labels from same-named columns of upstream nodes.
+    pub propagate: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ClassificationRule {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default = "default_rule_label")]
+    pub label: String,
+}
+
+fn default_rule_label() -> String {
+    "pii".to_string()
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        let rule = |name: &str, pattern: &str| ClassificationRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            label: default_rule_label(),
+        };
+        ClassificationConfig {
+            tags: vec!["pii".to_string(), "sensitive".to_string()],
+            meta_keys: vec!["pii".to_string(), "sensitivity".to_string(), "classification".to_string()],
+            rules: vec![
+                rule("email", r"(?i)(^|_)e_?mail(_address)?($|_)"),
+                rule("ssn", r"(?i)(^|_)(ssn|social_security(_number)?)($|_)"),
+                rule("phone", r"(?i)(^|_)(phone|mobile|cell)(_?(number|num|no))?($|_)"),
+                rule("dob", r"(?i)(^|_)(dob|date_of_birth|birth_?date)($|_)"),
+            ],
+            propagate: true,
+        }
+    }
+}
+
+/// Limits for `/models/:id/preview`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreviewConfig {
+    pub script: PathBuf,
+    pub default_rows: usize,
+    pub max_rows: usize,
+    pub max_bytes: usize,
+    pub timeout_secs: u64,
+    /// Only these schemas can be previewed (empty allows all).
+    pub allowed_schemas: Vec<String>,
+    /// Schemas that can never be previewed; wins over `allowed_schemas`.
+    pub denied_schemas: Vec<String>,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        PreviewConfig {
+            script: PathBuf::from("/backend/scripts/preview_relation.py"),
+            default_rows: 50,
+            max_rows: 500,
+            max_bytes: 1_000_000,
+            timeout_secs: 10,
+            allowed_schemas: vec![],
+            denied_schemas: vec![],
+        }
+    }
+}
+
+/// Column profiling job. Uses the preview schema allow/deny lists.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfilingConfig {
+    pub script: PathBuf,
+    pub top_k: usize,
+    /// Per relation.
+    pub timeout_secs: u64,
+    /// Seconds between scheduled profiling runs; 0 only profiles on request.
+    pub interval_secs: u64,
+}
+
+impl Default for ProfilingConfig {
+    fn default() -> Self {
+        ProfilingConfig {
+            script: PathBuf::from("/backend/scripts/profile_relation.py"),
+            top_k: 5,
+            timeout_secs: 60,
+            interval_secs: 0,
+        }
+    }
+}
+
+/// Embedded SQLite store for manifest snapshots, run results, source
+/// freshness, profiles, drift events, annotations and audit events.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct StoreConfig {
+    pub path: PathBuf,
+    /// Seconds between imports of dbt artifacts written outside the backend
+    /// (e.g. by the cron refresh); 0 only imports at startup and after
+    /// backend-run refreshes.
+    pub ingest_interval_secs: u64,
+    /// Manifest snapshots kept per project.
+    pub keep_snapshots: usize,
+    /// Days of run results, audit events and profiles kept; 0 keeps them all.
+    /// The latest profile of a relation is always kept.
+    pub retention_days: u64,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        StoreConfig {
+            path: PathBuf::from("/backend/cache/catalog.db"),
+            ingest_interval_secs: 300,
+            keep_snapshots: 30,
+            retention_days: 90,
+        }
+    }
+}
+
+/// API authentication. Off until at least one token, user or JWT issuer is
+/// configured; then every route group not listed in `anonymous_groups`
+/// needs credentials.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Route groups open without credentials: `metadata` (read-only catalog
+    /// routes), `data` (previews and profiles), `write` (everything that
+    /// isn't a GET) and `admin` (the audit log).
+    pub anonymous_groups: Vec<String>,
+    /// Static API tokens, sent as `Authorization: Bearer <token>`.
+    pub tokens: Vec<ApiToken>,
+    /// HTTP basic users with bcrypt password hashes (`htpasswd -nbB`).
+    pub users: Vec<BasicUser>,
+    pub jwt: Option<JwtConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ApiToken {
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BasicUser {
+    pub username: String,
+    pub password_hash: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// OIDC/JWT bearer tokens, verified against the keys of a JWKS file.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JwtConfig {
+    pub jwks_path: PathBuf,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    #[serde(default = "default_username_claim")]
+    pub username_claim: String,
+    /// Array of role names, or a space separated string.
+    #[serde(default = "default_roles_claim")]
+    pub roles_claim: String,
+    /// Algorithm of JWKS keys without their own `alg`, e.g. `RS256`.
+    #[serde(default)]
+    pub algorithm: Option<String>,
+}
+
+fn default_username_claim() -> String {
+    "sub".to_string()
+}
+
+fn default_roles_claim() -> String {
+    "roles".to_string()
+}
+
+/// Role-based visibility of nodes. Off until roles are configured; then a
+/// caller sees a node when any of their roles grants it.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RbacConfig {
+    pub roles: Vec<RoleConfig>,
+    /// Roles of authenticated callers that hold none of the configured roles.
+    pub default_roles: Vec<String>,
+    /// Roles of unauthenticated callers on anonymous route groups.
+    pub anonymous_roles: Vec<String>,
+}
+
+/// A role grants every node matching any of its lists.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RoleConfig {
+    pub name: String,
+    /// dbt package names; `*` grants every node.
+    #[serde(default)]
+    pub packages: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Model access levels: `public`, `protected` or `private`.
+    #[serde(default)]
+    pub access: Vec<String>,
+    /// Whether the role may read raw and compiled SQL.
+    #[serde(default = "default_true")]
+    pub raw_sql: bool,
+    /// Whether the role may read columns classified as PII/sensitive.
+    #[serde(default)]
+    pub sensitive_columns: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Catalog linting, served at `/lint` and by the `lint` subcommand.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// YAML rules file; built-in rules (descriptions, public contracts) when unset.
+    pub rules_path: Option<PathBuf>,
+}
+
+/// Model health scores, served at `/health` and in model docs.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// Run results per model considered for the run success component.
+    pub recent_runs: usize,
+    pub weights: HealthWeights,
+}
+
+/// Relative weights of the score components; only their ratios matter.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthWeights {
+    pub documentation: f64,
+    pub tests: f64,
+    pub runs: f64,
+    pub freshness: f64,
+    pub ownership: f64,
+    pub contract: f64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig { recent_runs: 5, weights: HealthWeights::default() }
+    }
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        HealthWeights { documentation: 25.0, tests: 25.0, runs: 20.0, freshness: 10.0, ownership: 10.0, contract: 10.0 }
+    }
+}
+
+impl AuthConfig {
+    pub fn enabled(&self) -> bool {
+        !self.tokens.is_empty() || !self.users.is_empty() || self.jwt.is_some()
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            listen_addr: "0.0.0.0:3000".to_string(),
+            cors_origins: vec!["*".to_string()],
+        }
+    }
+}
+
+impl Default for DbtConfig {
+    fn default() -> Self {
+        DbtConfig {
+            name: None,
+            project_dir: PathBuf::from("/backend/dbt_project"),
+            profiles_dir: None,
+            profile: None,
+            target: None,
+            duckdb_path: None,
+        }
+    }
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        ArtifactsConfig {
+            target_dir: None,
+            manifest_path: PathBuf::from("/backend/cache/enriched_manifest.json"),
+            enrich_script: PathBuf::from("/backend/scripts/enrich_manifest.py"),
+        }
+    }
+}
+
+impl ProjectConfig {
+    pub fn target_dir(&self) -> PathBuf {
+        self.target_dir
+            .clone()
+            .unwrap_or_else(|| self.project_dir.join("target"))
+    }
+
+    /// Column snapshot of the last refresh, written by the enrichment script.
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.manifest_path.with_extension("snapshot.json")
+    }
+
+    /// Append-only log of schema drift events, written by the enrichment script.
+    pub fn drift_path(&self) -> PathBuf {
+        self.manifest_path.with_extension("drift.jsonl")
+    }
+
+    /// Extra arguments appended to every dbt invocation.
+    pub fn cli_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(profiles_dir) = &self.profiles_dir {
+            args.push("--profiles-dir".to_string());
+            args.push(profiles_dir.display().to_string());
+        }
+        if let Some(profile) = &self.profile {
+            args.push("--profile".to_string());
+            args.push(profile.clone());
+        }
+        if let Some(target) = &self.target {
+            args.push("--target".to_string());
+            args.push(target.clone());
+        }
+        args
+    }
+}
+
+impl AppConfig {
+    /// The project served by the un-prefixed routes (`/models`, `/lineage`, ...).
+    pub fn default_project(&self) -> &ProjectConfig {
+        // `load` always leaves at least one project behind.
+        &self.projects[0]
+    }
+
+    pub fn project(&self, name: &str) -> Option<&ProjectConfig> {
+        self.projects.iter().find(|project| project.name == name)
+    }
+
+    pub fn listen_addr(&self) -> SocketAddr {
+        // Checked in `validate`, so this cannot fail once the config is loaded.
+        self.server.listen_addr.parse().expect("listen_addr was validated at startup")
+    }
+
+    /// Build the config from defaults, the TOML file, env vars and CLI flags.
+    pub fn load(cli: &Cli) -> Result<AppConfig, String> {
+        let mut config = match &cli.config {
+            Some(path) => Self::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            None => AppConfig::default(),
+        };
+        config.apply_overrides(cli);
+        config.resolve_projects(cli)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<AppConfig, String> {
+        info!("Loading config from {}", path.display());
+        let content = read_file(&path.display().to_string())
+            .map_err(|e| format!("Failed to read config file {}: {}", path.display(), e))?;
+        toml::from_str(&content)
+            .map_err(|e| format!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    fn apply_overrides(&mut self, cli: &Cli) {
+        if let Some(listen_addr) = &cli.listen_addr {
+            self.server.listen_addr = listen_addr.clone();
+        }
+        if let Some(cors_origins) = &cli.cors_origins {
+            self.server.cors_origins = cors_origins.clone();
+        }
+        if let Some(project_dir) = &cli.dbt_project_dir {
+            self.dbt.project_dir = project_dir.clone();
+        }
+        if let Some(profiles_dir) = &cli.dbt_profiles_dir {
+            self.dbt.profiles_dir = Some(profiles_dir.clone());
+        }
+        if let Some(profile) = &cli.dbt_profile {
+            self.dbt.profile = Some(profile.clone());
+        }
+        if let Some(target) = &cli.dbt_target {
+            self.dbt.target = Some(target.clone());
+        }
+        if let Some(target_dir) = &cli.target_dir {
+            self.artifacts.target_dir = Some(target_dir.clone());
+        }
+        if let Some(manifest_path) = &cli.manifest_path {
+            self.artifacts.manifest_path = manifest_path.clone();
+        }
+        if let Some(enrich_script) = &cli.enrich_script {
+            self.artifacts.enrich_script = enrich_script.clone();
+        }
+        if let Some(interval) = cli.refresh_interval {
+            self.refresh.interval_secs = interval;
+        }
+        if let Some(store_path) = &cli.store_path {
+            self.store.path = store_path.clone();
+        }
+    }
+
+    /// Fall back to the single project described by `[dbt]` and `[artifacts]`.
+    /// Project flags would be ignored next to `[[projects]]`, so that is an error.
+    fn resolve_projects(&mut self, cli: &Cli) -> Result<(), String> {
+        if !self.projects.is_empty() {
+            let project_flags = [
+                ("--dbt-project-dir", cli.dbt_project_dir.is_some()),
+                ("--dbt-profiles-dir", cli.dbt_profiles_dir.is_some()),
+                ("--dbt-profile", cli.dbt_profile.is_some()),
+                ("--dbt-target", cli.dbt_target.is_some()),
+                ("--target-dir", cli.target_dir.is_some()),
+                ("--manifest-path", cli.manifest_path.is_some()),
+            ];
+            let set: Vec<&str> = project_flags.iter().filter(|(_, set)| *set).map(|(flag, _)| *flag).collect();
+            if !set.is_empty() {
+                return Err(format!(
+                    "{} (or the matching CATALOG_* env vars) cannot be combined with [[projects]]; set them per project in the config file",
+                    set.join(", ")
+                ));
+            }
+            return Ok(());
+        }
+        self.projects.push(ProjectConfig {
+            name: self.dbt.name.clone().unwrap_or_else(|| "default".to_string()),
+            project_dir: self.dbt.project_dir.clone(),
+            manifest_path: self.artifacts.manifest_path.clone(),
+            target_dir: self.artifacts.target_dir.clone(),
+            profiles_dir: self.dbt.profiles_dir.clone(),
+            profile: self.dbt.profile.clone(),
+            target: self.dbt.target.clone(),
+            duckdb_path: self.dbt.duckdb_path.clone(),
+        });
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.server
+            .listen_addr
+            .parse::<SocketAddr>()
+            .map_err(|e| format!("server.listen_addr '{}' is not a valid socket address: {}", self.server.listen_addr, e))?;
+
+        if self.server.cors_origins.is_empty() {
+            return Err("server.cors_origins must list at least one origin (use \"*\" to allow any)".to_string());
+        }
+        for origin in &self.server.cors_origins {
+            if origin == "*" {
+                continue;
+            }
+            if !(origin.starts_with("http://") || origin.starts_with("https://")) {
+                return Err(format!("server.cors_origins entry '{}' must be \"*\" or start with http:// or https://", origin));
+            }
+            origin
+                .parse::<axum::http::HeaderValue>()
+                .map_err(|e| format!("server.cors_origins entry '{}' is not a valid origin: {}", origin, e))?;
+        }
+        if self.server.cors_origins.len() > 1 && self.server.cors_origins.iter().any(|o| o == "*") {
+            return Err("server.cors_origins cannot mix \"*\" with explicit origins".to_string());
+        }
+
+        for rule in &self.classification.rules {
+            regex::Regex::new(&rule.pattern)
+                .map_err(|e| format!("classification rule '{}' has an invalid pattern: {}", rule.name, e))?;
+        }
+
+        if self.preview.max_rows == 0 || self.preview.default_rows == 0 || self.preview.default_rows > self.preview.max_rows {
+            return Err("preview.default_rows must be between 1 and preview.max_rows".to_string());
+        }
+        if self.preview.max_bytes == 0 || self.preview.timeout_secs == 0 {
+            return Err("preview.max_bytes and preview.timeout_secs must be greater than 0".to_string());
+        }
+
+        if self.profiling.top_k == 0 || self.profiling.timeout_secs == 0 {
+            return Err("profiling.top_k and profiling.timeout_secs must be greater than 0".to_string());
+        }
+
+        for group in &self.auth.anonymous_groups {
+            if !crate::auth::ROUTE_GROUPS.contains(&group.as_str()) {
+                return Err(format!("auth.anonymous_groups entry '{}' must be one of {}", group, crate::auth::ROUTE_GROUPS.join(", ")));
+            }
+        }
+        for (i, token) in self.auth.tokens.iter().enumerate() {
+            if token.token.len() < 16 {
+                return Err(format!("auth token '{}' must be at least 16 characters long", token.name));
+            }
+            if self.auth.tokens[..i].iter().any(|other| other.token == token.token) {
+                return Err(format!("auth token '{}' reuses the token of another entry", token.name));
+            }
+        }
+        for user in &self.auth.users {
+            if !user.password_hash.starts_with("$2") {
+                return Err(format!("auth user '{}' needs a bcrypt password_hash", user.username));
+            }
+        }
+        if let Some(jwt) = &self.auth.jwt {
+            if !jwt.jwks_path.is_file() {
+                return Err(format!("auth.jwt.jwks_path '{}' does not exist", jwt.jwks_path.display()));
+            }
+            if let Some(algorithm) = &jwt.algorithm {
+                algorithm
+                    .parse::<jsonwebtoken::Algorithm>()
+                    .map_err(|_| format!("auth.jwt.algorithm '{}' is not a JWT signing algorithm", algorithm))?;
+            }
+        }
+
+        for (i, role) in self.rbac.roles.iter().enumerate() {
+            if self.rbac.roles[..i].iter().any(|other| other.name == role.name) {
+                return Err(format!("rbac role '{}' is configured more than once", role.name));
+            }
+            if let Some(access) = role.access.iter().find(|a| !["public", "protected", "private"].contains(&a.as_str())) {
+                return Err(format!("rbac role '{}': access '{}' must be public, protected or private", role.name, access));
+            }
+        }
+        for role in self.rbac.default_roles.iter().chain(&self.rbac.anonymous_roles) {
+            if !self.rbac.roles.iter().any(|r| &r.name == role) {
+                return Err(format!("rbac default/anonymous role '{}' is not configured", role));
+            }
+        }
+
+        if let Some(rules_path) = &self.lint.rules_path {
+            crate::lint::parse_rules(rules_path)?;
+        }
+
+        let weights = &self.health.weights;
+        let weights = [weights.documentation, weights.tests, weights.runs, weights.freshness, weights.ownership, weights.contract];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || weights.iter().sum::<f64>() == 0.0 {
+            return Err("health.weights must not be negative and at least one must be greater than 0".to_string());
+        }
+        if self.health.recent_runs == 0 {
+            return Err("health.recent_runs must be greater than 0".to_string());
+        }
+
+        if self.store.keep_snapshots == 0 {
+            return Err("store.keep_snapshots must be greater than 0".to_string());
+        }
+
+        for (i, project) in self.projects.iter().enumerate() {
+            project.validate()?;
+            if self.projects[..i].iter().any(|other| other.name == project.name) {
+                return Err(format!("Project name '{}' is configured more than once", project.name));
+            }
+        }
+
+        if self.refresh.interval_secs > 0 && !self.artifacts.enrich_script.is_file() {
+            return Err(format!("artifacts.enrich_script '{}' does not exist but refresh.interval_secs is set", self.artifacts.enrich_script.display()));
+        }
+
+        Ok(())
+    }
+}
+
+impl ProjectConfig {
+    fn validate(&self) -> Result<(), String> {
+        let name = &self.name;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("Project name '{}' must be non-empty and only use letters, digits, '_' or '-'", name));
+        }
+        if !self.project_dir.is_dir() {
+            return Err(format!("project '{}': project_dir '{}' does not exist or is not a directory", name, self.project_dir.display()));
+        }
+        if let Some(profiles_dir) = &self.profiles_dir {
+            if !profiles_dir.is_dir() {
+                return Err(format!("project '{}': profiles_dir '{}' does not exist or is not a directory", name, profiles_dir.display()));
+            }
+        }
+        if self.profile.as_deref() == Some("") || self.target.as_deref() == Some("") {
+            return Err(format!("project '{}': profile and target must not be empty when set", name));
+        }
+
+        match self.manifest_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() && !dir.is_dir() => {
+                return Err(format!("project '{}': directory for manifest_path '{}' does not exist", name, self.manifest_path.display()));
+            }
+            _ => {}
+        }
+        if !self.manifest_path.exists() {
+            // Not fatal: the first refresh creates it.
+            warn!("Enriched manifest {} for project '{}' does not exist yet; run a cache refresh", self.manifest_path.display(), name);
+        }
+        Ok(())
+    }
+}
+
+/// Install the loaded config for the lifetime of the process.
+pub fn init(config: AppConfig) {
+    CONFIG.set(config).expect("config::init called twice");
+}
+
+/// The process-wide config. Panics if `init` has not run yet.
+pub fn get() -> &'static AppConfig {
+    CONFIG.get().expect("config::init must run before the config is read")
+}
@@ -0,0 +1,107 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Documentation and test coverage of one model.
+#[derive(Serialize)]
+pub struct ModelCoverage {
+    pub unique_id: String,
+    pub name: String,
+    pub described: bool,
+    pub columns: usize,
+    pub documented_columns: usize,
+    pub tested_columns: usize,
+    pub tests: usize,
+}
+
+#[derive(Serialize)]
+pub struct CoverageSummary {
+    pub models: usize,
+    pub described_models: usize,
+    pub tested_models: usize,
+    pub columns: usize,
+    pub documented_columns: usize,
+    pub tested_columns: usize,
+    /// Share of models and columns with a description, 0-100.
+    pub documented_pct: f64,
+    /// Share of models with at least one test, 0-100.
+    pub tested_pct: f64,
+}
+
+fn has_description(node: &Value) -> bool {
+    node.get("description").and_then(|d| d.as_str()).is_some_and(|d| !d.trim().is_empty())
+}
+
+/// Models a test runs against: its `attached_node`, else its dependencies.
+fn tested_nodes(test: &Value) -> Vec<&str> {
+    if let Some(attached) = test.get("attached_node").and_then(|a| a.as_str()) {
+        return vec![attached];
+    }
+    test.get("depends_on")
+        .and_then(|d| d.get("nodes"))
+        .and_then(|n| n.as_array())
+        .map_or(vec![], |nodes| nodes.iter().filter_map(|n| n.as_str()).collect())
+}
+
+/// Coverage of every model in a manifest, ordered by unique_id.
+pub fn model_coverage(manifest: &Value) -> Vec<ModelCoverage> {
+    let Some(nodes) = manifest.get("nodes").and_then(|n| n.as_object()) else {
+        return vec![];
+    };
+
+    // unique_id -> (test count, lowercased tested column names)
+    let mut tests: HashMap<&str, (usize, Vec<String>)> = HashMap::new();
+    for test in nodes.values().filter(|n| matches!(n.get("resource_type").and_then(|r| r.as_str()), Some("test"))) {
+        let column = test.get("column_name").and_then(|c| c.as_str()).map(str::to_lowercase);
+        for unique_id in tested_nodes(test) {
+            let entry = tests.entry(unique_id).or_default();
+            entry.0 += 1;
+            entry.1.extend(column.clone());
+        }
+    }
+
+    let mut coverage: Vec<ModelCoverage> = nodes
+        .iter()
+        .filter(|(_, node)| node.get("resource_type").and_then(|r| r.as_str()) == Some("model"))
+        .map(|(unique_id, node)| {
+            let columns = node.get("columns").and_then(|c| c.as_object());
+            let (test_count, tested) = tests.get(unique_id.as_str()).cloned().unwrap_or_default();
+            ModelCoverage {
+                unique_id: unique_id.clone(),
+                name: node.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+                described: has_description(node),
+                columns: columns.map_or(0, |c| c.len()),
+                documented_columns: columns.map_or(0, |c| c.values().filter(|col| has_description(col)).count()),
+                tested_columns: columns.map_or(0, |c| c.keys().filter(|name| tested.contains(&name.to_lowercase())).count()),
+                tests: test_count,
+            }
+        })
+        .collect();
+    coverage.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+    coverage
+}
+
+fn pct(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        (part as f64 * 1000.0 / total as f64).round() / 10.0
+    }
+}
+
+pub fn summarize(models: &[ModelCoverage]) -> CoverageSummary {
+    let described_models = models.iter().filter(|m| m.described).count();
+    let tested_models = models.iter().filter(|m| m.tests > 0).count();
+    let columns = models.iter().map(|m| m.columns).sum();
+    let documented_columns = models.iter().map(|m| m.documented_columns).sum();
+    CoverageSummary {
+        models: models.len(),
+        described_models,
+        tested_models,
+        columns,
+        documented_columns,
+        tested_columns: models.iter().map(|m| m.tested_columns).sum(),
+        documented_pct: pct(described_models + documented_columns, models.len() + columns),
+        tested_pct: pct(tested_models, models.len()),
+    }
+}
@@ -1,78 +1,32 @@
-use axum::Json;
+use axum::{http::{header, HeaderName, StatusCode}, Json};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use serde_yaml::Value as YamlValue;
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
 use core::str;
-use std::{fs, path::Path, process::{Command, Output}};
+use std::{fs, process::Command};
 use log::{info, error};
 use std::collections::HashMap;
 
-// Updated Node struct
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Node {
-    pub alias: String,
-    pub config: Config,
-    pub depends_on: Dependencies,
-    pub name: String,
-    pub original_file_path: String,
-    pub package_name: String,
-    pub resource_type: String,
-    pub tags: Vec<String>,
-    pub unique_id: String,
-}
-
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Config {
-    pub access: Option<String>,
-    pub alias: Option<String>,
-    pub batch_size: Option<u32>,
-    pub begin: Option<String>,
-    pub column_types: Option<HashMap<String, String>>,
-    pub concurrent_batches: Option<u32>,
-    pub contract: Option<Contract>,
-    pub database: Option<String>,
-    pub docs: Option<Docs>,
-    pub enabled: Option<bool>,
-    pub event_time: Option<String>,
-    pub full_refresh: Option<bool>,
-    pub grants: Option<HashMap<String, Vec<String>>>,
-    pub group: Option<String>,
-    pub incremental_strategy: Option<String>,
-    pub lookback: Option<u32>,
-    pub materialized: Option<String>,
-    pub meta: Option<HashMap<String, String>>,
-    pub on_configuration_change: Option<String>,
-    pub on_schema_change: Option<String>,
-    pub packages: Option<Vec<String>>,
-    pub persist_docs: Option<HashMap<String, String>>,
-    pub post_hook: Option<Vec<String>>,
-    pub pre_hook: Option<Vec<String>>,
-    pub quoting: Option<HashMap<String, bool>>,
-    pub schema: Option<String>,
-    pub tags: Option<Vec<String>>,
-    pub unique_key: Option<String>,
+use crate::annotations::{self, Annotation};
+use crate::classification::{self, ColumnClassification, NodeClassifications};
+use crate::config::ProjectConfig;
+use crate::docs;
+use crate::glossary;
+use crate::governance::{self, Governance};
+use crate::graph::CatalogGraph;
+use crate::health::{self, HealthScore};
+use crate::profiling;
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+
+/// Path params of the `/models/:id` style routes.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct ModelPath {
+    /// Model name or unique_id.
+    pub id: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Contract {
-    pub alias_types: Option<bool>,
-    pub enforced: Option<bool>,
-}
-
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Docs {
-    pub node_color: Option<String>,
-    pub show: Option<bool>,
-}
-
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Dependencies {
-    pub macros: Option<Vec<String>>,
-    pub nodes: Option<Vec<String>>,
-}
-
-const MANIFEST_PATH: &str = "/backend/cache/enriched_manifest.json";
-
 /// Clean the output of DBT command to remove logs and retain only JSON.
 pub fn clean_dbt_output(output: &[u8]) -> String {
     let stdout = str::from_utf8(output).unwrap_or_default();
@@ -98,7 +52,10 @@ pub fn clean_dbt_output(output: &[u8]) -> String {
 
 
 /// Helper function to run a DBT command and clean its output.
-pub fn run_dbt_command(dbt_project_dir: &str, args: &[&str]) -> Result<String, String> {
+pub fn run_dbt_command(project: &ProjectConfig, args: &[&str]) -> Result<String, String> {
+    let dbt_project_dir = project.project_dir.display().to_string();
+    let dbt_project_dir = dbt_project_dir.as_str();
+
     // Ensure the directory exists
     if !std::path::Path::new(dbt_project_dir).exists() {
         return Err(format!("DBT project directory does not exist: {}", dbt_project_dir));
@@ -112,9 +69,10 @@ pub fn run_dbt_command(dbt_project_dir: &str, args: &[&str]) -> Result<String, S
     );
     info!("Running DBT command: {}", command_str);
 
-    // Run the command
+    // Run the command with the configured profile/target
     let output = Command::new("dbt")
         .args(args)
+        .args(project.cli_args())
         .current_dir(dbt_project_dir)
         .output()
         .map_err(|e| format!("Failed to run dbt command: {}", e))?;
@@ -141,8 +99,19 @@ pub fn run_dbt_command(dbt_project_dir: &str, args: &[&str]) -> Result<String, S
 }
 
 
-pub async fn get_models() -> Json<Vec<String>> {
-    let cache_path = MANIFEST_PATH;
+/// A model as listed by `/models`.
+#[derive(Serialize, ToSchema)]
+pub struct ModelSummary {
+    pub name: String,
+    pub unique_id: String,
+    /// Unset when the store can't be read.
+    pub health: Option<HealthScore>,
+}
+
+/// The project's models with their health scores.
+#[utoipa::path(get, path = "/models", tag = "models", responses((status = 200, body = Vec<ModelSummary>)))]
+pub async fn get_models(Project(project): Project, access: Access) -> Json<Vec<ModelSummary>> {
+    let cache_path = &project.manifest_path;
     match fs::read_to_string(cache_path) {
         Ok(enriched_manifest) => {
             let manifest_json: serde_json::Value = match serde_json::from_str(&enriched_manifest) {
@@ -153,21 +122,31 @@ pub async fn get_models() -> Json<Vec<String>> {
                 }
             };
 
-            // Extract model names
-            let model_names = manifest_json["nodes"]
+            // Health inputs are loaded once for every model of the project
+            let graph = CatalogGraph::build(&load_all_manifests());
+            let inputs = health::HealthInputs::load(project, &manifest_json, &graph, None)
+                .map_err(|e| error!("Failed to load health inputs for project '{}': {}", project.name, e))
+                .ok();
+
+            // Extract model summaries
+            let models = manifest_json["nodes"]
                 .as_object()
                 .unwrap_or(&serde_json::Map::new())
                 .iter()
-                .filter_map(|(_, node)| {
-                    if node["resource_type"] == "model" {
-                        node["name"].as_str().map(String::from)
+                .filter_map(|(unique_id, node)| {
+                    if node["resource_type"] == "model" && access.can_see(node) {
+                        node["name"].as_str().map(|name| ModelSummary {
+                            name: name.to_string(),
+                            unique_id: unique_id.clone(),
+                            health: inputs.as_ref().map(|inputs| inputs.score(&manifest_json, unique_id, node)),
+                        })
                     } else {
                         None
                     }
                 })
                 .collect::<Vec<_>>();
 
-            Json(model_names)
+            Json(models)
         }
         Err(e) => {
             error!("Failed to read enriched manifest: {}", e);
@@ -177,154 +156,283 @@ pub async fn get_models() -> Json<Vec<String>> {
 }
 
 
-pub async fn get_model_docs(path_params: axum::extract::Path<String>) -> Json<Value> {
-    let model_id = path_params.0;
-    let cache_path = MANIFEST_PATH;
-    // Load the enriched manifest
-    let manifest_data = fs::read_to_string(cache_path)
-        .expect("Failed to read enriched_manifest.json. Ensure the cache is built.");
-    let manifest_json: Value = serde_json::from_str(&manifest_data)
-        .expect("Failed to parse enriched_manifest.json");
-
-    // Extract nodes
-    let nodes = manifest_json
-        .get("nodes")
-        .and_then(|n| n.as_object())
-        .expect("Nodes not found in enriched_manifest.json");
-
-    // Find the model
-    if let Some(model) = nodes.values().find(|node| {
-        node.get("unique_id")
-            .and_then(|id| id.as_str())
-            .map_or(false, |id| id.ends_with(&model_id))
-    }) {
-        // Extract general information
-        let general = json!({
-            "name": model.get("name").unwrap_or(&json!("Unknown")),
-            "description": model.get("description").unwrap_or(&json!("No description available")),
-            "materialized": model.get("config")
-                .and_then(|c| c.get("materialized"))
-                .unwrap_or(&json!("Unknown")),
-            "schema": model.get("schema").unwrap_or(&json!("Unknown")),
-            "database": model.get("database").unwrap_or(&json!("Unknown")),
-            "primary_keys": model.get("primary_key").unwrap_or(&json!([])),
-            "tags": model.get("tags").unwrap_or(&json!([]))
-        });
-
-        // Extract columns
-        let columns = model
-            .get("columns")
-            .and_then(|c| c.as_object())
-            .map(|cols| {
-                cols.values()
-                    .map(|col| {
-                        json!({
-                            "name": col.get("name").unwrap_or(&json!("Unknown")),
-                            "type": col.get("type").unwrap_or(&json!("Unknown")),
-                            "description": col.get("comment").unwrap_or(&json!("No description available"))
-                        })
-                    })
-                    .collect::<Vec<Value>>()
+/// Locate a model by matching `unique_id` (may include prefixes).
+pub fn find_model<'a>(manifest_json: &'a Value, model_id: &str) -> Option<&'a Value> {
+    let nodes = manifest_json.get("nodes").and_then(|n| n.as_object())?;
+    let unique_id = |node: &Value| node.get("unique_id").and_then(|id| id.as_str()).map(String::from);
+
+    // Whole-segment matches first so `orders` doesn't resolve to `stg_orders`
+    let qualified = format!(".{}", model_id);
+    nodes
+        .values()
+        .find(|node| unique_id(node).is_some_and(|id| id == model_id || id.ends_with(&qualified)))
+        .or_else(|| {
+            nodes.values().find(|node| {
+                unique_id(node).is_some_and(|id| id.ends_with(model_id)) // Match suffix for flexibility
             })
-            .unwrap_or_else(|| vec![]);
-
-        // Extract SQL-related information
-        let sql = json!({
-            "relation_name": model.get("relation_name").unwrap_or(&json!("Unknown")),
-            "raw_code": model.get("raw_code").unwrap_or(&json!("No SQL code available"))
-        });
-
-        // Combine cleaned data
-        let cleaned_model = json!({
-            "general": general,
-            "columns": columns,
-            "sql": sql
-        });
-
-        return Json(cleaned_model);
-    }
+        })
+}
+
+/// The cleaned view of a model served by `/model_docs/:id` and `/models/:id`.
+#[derive(Serialize, ToSchema)]
+pub struct ModelDocs {
+    pub general: ModelGeneral,
+    pub governance: Governance,
+    pub columns: Vec<ModelColumn>,
+    pub sql: ModelSql,
+    /// Unset when the store can't be read.
+    pub health: Option<HealthScore>,
+}
 
-    panic!("Model not found in enriched_manifest.json: {}", model_id);
+#[derive(Serialize, ToSchema)]
+pub struct ModelGeneral {
+    pub name: String,
+    /// Markdown, as written in the YAML.
+    pub description: String,
+    /// Markdown, with `doc()` references resolved.
+    pub description_resolved: String,
+    /// The resolved description rendered to sanitized HTML.
+    pub description_html: String,
+    pub materialized: String,
+    pub schema: String,
+    pub database: String,
+    pub primary_keys: Vec<String>,
+    pub tags: Vec<String>,
+    pub access: Option<String>,
+    pub group: Option<String>,
+    /// A number or a string, as declared in the model's YAML.
+    pub version: Option<Value>,
+    pub latest_version: Option<Value>,
+    pub annotation: Option<Annotation>,
+    /// Names of the glossary terms linked to the model.
+    pub glossary_terms: Vec<String>,
+}
+
+/// A documented column, or a sensitive one the caller may not read.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum ModelColumn {
+    Documented(Box<DocumentedColumn>),
+    Redacted(RedactedColumn),
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentedColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+    /// The YAML description, else the warehouse comment.
+    pub description: String,
+    pub description_resolved: String,
+    pub description_html: String,
+    pub annotation: Option<Annotation>,
+    pub glossary_terms: Vec<String>,
+    pub classification: Option<ColumnClassification>,
+    /// Latest profiling statistics as reported by the warehouse.
+    pub profile: Option<Value>,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct RedactedColumn {
+    pub name: String,
+    /// Always true.
+    pub redacted: bool,
+    pub classification: Option<ColumnClassification>,
+}
 
+#[derive(Serialize, ToSchema)]
+pub struct ModelSql {
+    pub relation_name: String,
+    /// `Redacted` for callers without access to raw SQL.
+    pub raw_code: String,
+}
 
-pub async fn get_model_details(path_params: axum::extract::Path<String>) -> Json<Value> {
-    let model_id = path_params.0; // e.g., "my_first_dbt_model"
-    let cache_path = MANIFEST_PATH;
-    // Load the enriched manifest
-    let manifest_data = fs::read_to_string(cache_path)
-        .expect("Failed to read enriched_manifest.json. Ensure the cache is built.");
-    let manifest_json: Value = serde_json::from_str(&manifest_data)
-        .expect("Failed to parse enriched_manifest.json");
-
-    // Extract nodes
-    let nodes = manifest_json
-        .get("nodes")
-        .and_then(|n| n.as_object())
-        .expect("Nodes not found in enriched_manifest.json");
-
-    // Locate the model by matching `unique_id` (may include prefixes)
-    if let Some(model) = nodes.values().find(|node| {
-        node.get("unique_id")
-            .and_then(|id| id.as_str())
-            .map_or(false, |id| id.ends_with(&model_id)) // Match suffix for flexibility
-    }) {
-        // Extract general information
-        let general = json!({
-            "name": model.get("name").unwrap_or(&json!("Unknown")),
-            "description": model.get("description").unwrap_or(&json!("No description available")),
-            "materialized": model.get("config")
-                .and_then(|c| c.get("materialized"))
-                .unwrap_or(&json!("Unknown")),
-            "schema": model.get("schema").unwrap_or(&json!("Unknown")),
-            "database": model.get("database").unwrap_or(&json!("Unknown")),
-            "primary_keys": model.get("primary_key").unwrap_or(&json!([])),
-            "tags": model.get("tags").unwrap_or(&json!([]))
-        });
-
-        // Extract columns
-        let columns = model
-            .get("columns")
-            .and_then(|c| c.as_object())
-            .map(|cols| {
-                cols.values()
-                    .map(|col| {
-                        json!({
-                            "name": col.get("name").unwrap_or(&json!("Unknown")),
-                            "type": col.get("type").unwrap_or(&json!("Unknown")),
-                            "description": col.get("comment").unwrap_or(&json!("No description available"))
-                        })
-                    })
-                    .collect::<Vec<Value>>()
-            })
-            .unwrap_or_else(|| vec![]);
-
-        // Extract SQL-related information
-        let sql = json!({
-            "relation_name": model.get("relation_name").unwrap_or(&json!("Unknown")),
-            "raw_code": model.get("raw_code").unwrap_or(&json!("No SQL code available"))
-        });
-
-        // Combine cleaned data
-        let cleaned_model = json!({
-            "general": general,
-            "columns": columns,
-            "sql": sql
-        });
-
-        return Json(cleaned_model);
+fn text_or(node: &Value, key: &str, default: &str) -> String {
+    node.get(key).and_then(|v| v.as_str()).unwrap_or(default).to_string()
+}
+
+fn text_list(node: &Value, key: &str) -> Vec<String> {
+    node.get(key)
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|item| item.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// The model's docs as served by `/model_docs/:id`. Column classifications
+/// are propagated through lineage and health looks at upstream sources, so
+/// the graph and classifications are built once and passed in.
+pub fn model_docs(
+    project: &ProjectConfig,
+    access: &Access,
+    manifest_json: &Value,
+    graph: &CatalogGraph,
+    classifications: &HashMap<String, NodeClassifications>,
+    model: &Value,
+) -> ModelDocs {
+    let unique_id = model.get("unique_id").and_then(|id| id.as_str()).unwrap_or_default();
+    let hidden_columns = access.hidden_columns_of(classifications).remove(unique_id).unwrap_or_default();
+
+    // User annotations live in the store, shown next to the dbt descriptions
+    let mut annotations = annotations::load_annotations(project, unique_id);
+    let mut glossary_terms = glossary::linked_terms(project, unique_id);
+
+    // Extract general information
+    let description = text_or(model, "description", "No description available");
+    let description_resolved = docs::resolve_doc_refs(manifest_json, &description);
+    let general = ModelGeneral {
+        name: text_or(model, "name", "Unknown"),
+        description_html: docs::render_markdown(&description_resolved),
+        description_resolved,
+        description,
+        materialized: model.get("config").map_or("Unknown".to_string(), |c| text_or(c, "materialized", "Unknown")),
+        schema: text_or(model, "schema", "Unknown"),
+        database: text_or(model, "database", "Unknown"),
+        primary_keys: text_list(model, "primary_key"),
+        tags: text_list(model, "tags"),
+        access: model.get("access").and_then(|a| a.as_str()).map(String::from),
+        group: model.get("group").and_then(|g| g.as_str()).map(String::from),
+        version: model.get("version").filter(|v| !v.is_null()).cloned(),
+        latest_version: model.get("latest_version").filter(|v| !v.is_null()).cloned(),
+        annotation: annotations.remove(""),
+        glossary_terms: glossary_terms.remove("").unwrap_or_default(),
+    };
+
+    let mut classifications = classifications.get(unique_id).cloned().unwrap_or_default();
+    let model_profile = profiling::load_profiles(project).remove(unique_id);
+
+    // Extract columns
+    let columns = model
+        .get("columns")
+        .and_then(|c| c.as_object())
+        .map(|cols| {
+            cols.values()
+                .map(|col| {
+                    let name = col.get("name").and_then(|n| n.as_str()).unwrap_or("Unknown");
+                    let key = name.to_lowercase();
+                    if hidden_columns.contains(&key) {
+                        return ModelColumn::Redacted(RedactedColumn {
+                            name: name.to_string(),
+                            redacted: true,
+                            classification: classifications.remove(&key),
+                        });
+                    }
+                    // Prefer the YAML description, fall back to the warehouse comment
+                    let description = col
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .filter(|d| !d.is_empty())
+                        .or_else(|| col.get("comment").and_then(|c| c.as_str()))
+                        .unwrap_or("No description available");
+                    let description_resolved = docs::resolve_doc_refs(manifest_json, description);
+                    ModelColumn::Documented(Box::new(DocumentedColumn {
+                        name: name.to_string(),
+                        data_type: text_or(col, "type", "Unknown"),
+                        description: description.to_string(),
+                        description_html: docs::render_markdown(&description_resolved),
+                        description_resolved,
+                        annotation: annotations.remove(&key),
+                        glossary_terms: glossary_terms.remove(&key).unwrap_or_default(),
+                        classification: classifications.remove(&key),
+                        profile: model_profile.as_ref().and_then(|p| {
+                            p.columns.iter().find(|(col, _)| col.eq_ignore_ascii_case(name)).map(|(_, stats)| stats.clone())
+                        }),
+                    }))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Extract SQL-related information
+    let sql = ModelSql {
+        relation_name: text_or(model, "relation_name", "Unknown"),
+        raw_code: if access.sees_raw_sql() {
+            text_or(model, "raw_code", "No SQL code available")
+        } else {
+            "Redacted".to_string()
+        },
+    };
+
+    ModelDocs {
+        general,
+        governance: governance::extract(model, manifest_json),
+        columns,
+        sql,
+        health: health::model_health(project, manifest_json, graph, model),
     }
+}
+/// Model docs of a visible model; hidden models look the same as missing ones.
+fn find_model_docs(project: &ProjectConfig, access: &Access, model_id: &str) -> Result<Json<ModelDocs>, (StatusCode, String)> {
+    let manifest_json = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    match find_model(&manifest_json, model_id).filter(|model| access.can_see(model)) {
+        Some(model) => {
+            let graph = CatalogGraph::build(&load_all_manifests());
+            let classifications = classification::classify(&graph);
+            Ok(Json(model_docs(project, access, &manifest_json, &graph, &classifications, model)))
+        }
+        None => Err((StatusCode::NOT_FOUND, format!("Model not found: {}", model_id))),
+    }
+}
 
-    panic!("Model not found in enriched manifest: {}", model_id);
+/// Documentation of a model: general info, governance, columns and SQL.
+#[utoipa::path(
+    get,
+    path = "/model_docs/{id}",
+    tag = "models",
+    params(ModelPath),
+    responses(
+        (status = 200, body = ModelDocs),
+        (status = 404, description = "Unknown or hidden model", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_model_docs(
+    Project(project): Project,
+    access: Access,
+    axum::extract::Path(ModelPath { id: model_id }): axum::extract::Path<ModelPath>,
+) -> Result<Json<ModelDocs>, (StatusCode, String)> {
+    find_model_docs(project, &access, &model_id)
 }
 
+/// Same as `/model_docs/{id}`.
+#[utoipa::path(
+    get,
+    path = "/models/{id}",
+    tag = "models",
+    params(ModelPath),
+    responses(
+        (status = 200, body = ModelDocs),
+        (status = 404, description = "Unknown or hidden model", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_model_details(
+    Project(project): Project,
+    access: Access,
+    axum::extract::Path(ModelPath { id: model_id }): axum::extract::Path<ModelPath>,
+) -> Result<Json<ModelDocs>, (StatusCode, String)> {
+    find_model_docs(project, &access, &model_id)
+}
 
-pub async fn get_manifest() -> String {
-    let cache_path = MANIFEST_PATH;
-    let manifest_content = fs::read_to_string(cache_path)
+/// The enriched dbt manifest, filtered to what the caller may see.
+#[utoipa::path(
+    get,
+    path = "/manifest",
+    tag = "models",
+    responses((status = 200, description = "dbt manifest (see the dbt manifest schema)", body = Object))
+)]
+pub async fn get_manifest(Project(project): Project, access: Access) -> ([(HeaderName, &'static str); 1], String) {
+    let cache_path = &project.manifest_path;
+    let manifest = fs::read_to_string(cache_path)
+        .unwrap_or_else(|_| "{}".to_string());
+    let json = [(header::CONTENT_TYPE, "application/json")];
+    if !access.is_restricted() {
+        return (json, manifest);
+    }
+    // Restricted callers get the manifest minus what they may not see
+    let manifest = serde_json::from_str(&manifest)
+        .map(|manifest| access.filter_manifest(manifest).to_string())
         .unwrap_or_else(|_| "{}".to_string());
-    manifest_content
+    (json, manifest)
 }
 
 
@@ -0,0 +1,123 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const DIFF_SECTIONS: &[&str] = &["nodes", "sources", "exposures", "metrics"];
+
+/// One difference between two manifests.
+#[derive(Serialize)]
+pub struct ManifestChange {
+    pub unique_id: String,
+    pub resource_type: String,
+    pub column: Option<String>,
+    /// `added`, `removed`, `column_added`, `column_removed`, `column_retyped`,
+    /// `description_changed`, `materialization_changed` or `dependencies_changed`.
+    pub kind: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+fn entries(manifest: &Value) -> Map<String, Value> {
+    DIFF_SECTIONS
+        .iter()
+        .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+        .flat_map(|section| section.iter().map(|(id, node)| (id.clone(), node.clone())))
+        .collect()
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn description(node: &Value) -> Option<String> {
+    text(node, "description")
+}
+
+fn materialized(node: &Value) -> Option<String> {
+    node.get("config").and_then(|c| text(c, "materialized"))
+}
+
+fn dependencies(node: &Value) -> Option<String> {
+    let mut nodes: Vec<&str> = node
+        .get("depends_on")
+        .and_then(|d| d.get("nodes"))
+        .and_then(|n| n.as_array())
+        .map_or(vec![], |nodes| nodes.iter().filter_map(|n| n.as_str()).collect());
+    nodes.sort_unstable();
+    Some(nodes.join(", "))
+}
+
+type Field = fn(&Value) -> Option<String>;
+
+/// Node fields compared between the manifests, with the change they report.
+const COMPARED_FIELDS: &[(&str, Field)] = &[
+    ("description_changed", description),
+    ("materialization_changed", materialized),
+    ("dependencies_changed", dependencies),
+];
+
+/// Warehouse type from the enriched manifest, else the declared one.
+fn column_type(column: &Value) -> Option<String> {
+    text(column, "type").or_else(|| text(column, "data_type"))
+}
+
+/// Columns keyed by lowercased name, which is how the warehouse compares them.
+fn columns(node: &Value) -> Map<String, Value> {
+    node.get("columns")
+        .and_then(|c| c.as_object())
+        .map(|columns| columns.iter().map(|(name, col)| (name.to_lowercase(), col.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// What changed from `old` to `new`, ordered by unique_id.
+pub fn diff_manifests(old: &Value, new: &Value) -> Vec<ManifestChange> {
+    let (old, new) = (entries(old), entries(new));
+    let mut ids: Vec<&String> = old.keys().chain(new.keys()).collect();
+    ids.sort();
+    ids.dedup();
+
+    let mut changes = vec![];
+    for unique_id in ids {
+        let change = |node: &Value, column: Option<&str>, kind: &str, before: Option<String>, after: Option<String>| ManifestChange {
+            unique_id: unique_id.clone(),
+            resource_type: text(node, "resource_type").unwrap_or_default(),
+            column: column.map(String::from),
+            kind: kind.to_string(),
+            before,
+            after,
+        };
+        let (before, after) = match (old.get(unique_id), new.get(unique_id)) {
+            (Some(before), Some(after)) => (before, after),
+            (Some(before), None) => {
+                changes.push(change(before, None, "removed", None, None));
+                continue;
+            }
+            (None, Some(after)) => {
+                changes.push(change(after, None, "added", None, None));
+                continue;
+            }
+            (None, None) => continue,
+        };
+
+        for (kind, field) in COMPARED_FIELDS {
+            let (was, is) = (field(before), field(after));
+            if was != is {
+                changes.push(change(after, None, kind, was, is));
+            }
+        }
+
+        let (old_columns, new_columns) = (columns(before), columns(after));
+        for (name, column) in &old_columns {
+            match new_columns.get(name) {
+                None => changes.push(change(after, Some(name), "column_removed", column_type(column), None)),
+                Some(current) if column_type(column) != column_type(current) => {
+                    changes.push(change(after, Some(name), "column_retyped", column_type(column), column_type(current)))
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, column) in new_columns.iter().filter(|(name, _)| !old_columns.contains_key(*name)) {
+            changes.push(change(after, Some(name), "column_added", None, column_type(column)));
+        }
+    }
+    changes
+}
@@ -0,0 +1,258 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use pulldown_cmark::{html, Options, Parser};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::projects::{load_manifest, manifest_project_name, Project};
+
+static DOC_REF: OnceLock<Regex> = OnceLock::new();
+
+/// Doc blocks may reference each other; deeper chains are left unresolved.
+const MAX_DOC_DEPTH: usize = 5;
+
+/// Bytes of doc block contents one description may pull in. References
+/// past it are kept as written, so blocks that each reference another many
+/// times can't multiply into an enormous description.
+const MAX_RESOLVED_BYTES: usize = 1 << 20;
+
+#[derive(Serialize, ToSchema)]
+pub struct DocBlockSummary {
+    pub unique_id: String,
+    pub name: String,
+    pub package_name: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocBlock {
+    pub unique_id: String,
+    pub name: String,
+    pub package_name: String,
+    pub original_file_path: Option<String>,
+    /// The block as written.
+    pub markdown: String,
+    /// Rendered and sanitized, with `doc()` references resolved.
+    pub html: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct DocPath {
+    /// Doc block name or unique_id, e.g. `__overview__`.
+    id: String,
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+/// A doc block by unique_id, by `package` and name, or by name alone,
+/// preferring the project's own package the way dbt does.
+fn find_doc<'a>(manifest: &'a Value, package: Option<&str>, name: &str) -> Option<(&'a String, &'a Value)> {
+    let docs = manifest.get("docs").and_then(|d| d.as_object())?;
+    if let Some(found) = docs.get_key_value(name) {
+        return Some(found);
+    }
+    let own_package = manifest_project_name(manifest);
+    let mut matches: Vec<(&String, &Value)> = docs
+        .iter()
+        .filter(|(_, doc)| doc.get("name").and_then(|n| n.as_str()) == Some(name))
+        .filter(|(_, doc)| package.is_none_or(|p| doc.get("package_name").and_then(|n| n.as_str()) == Some(p)))
+        .collect();
+    matches.sort_by_key(|(unique_id, doc)| {
+        (doc.get("package_name").and_then(|p| p.as_str()) != own_package, unique_id.as_str())
+    });
+    matches.into_iter().next()
+}
+
+/// Replace `{{ doc('name') }}` and `{{ doc('package', 'name') }}` with the
+/// contents of the doc block. Unknown references, and references back to a
+/// block that is being expanded, are kept as written.
+pub fn resolve_doc_refs(manifest: &Value, markdown: &str) -> String {
+    let mut budget = MAX_RESOLVED_BYTES;
+    expand_doc_refs(manifest, markdown, &mut vec![], &mut budget)
+}
+
+/// `markdown` with its references expanded depth-first; `stack` holds the
+/// unique_ids of the blocks being expanded.
+fn expand_doc_refs<'a>(manifest: &'a Value, markdown: &str, stack: &mut Vec<&'a str>, budget: &mut usize) -> String {
+    let doc_ref = DOC_REF.get_or_init(|| {
+        Regex::new(r#"\{\{\s*doc\(\s*['"]([^'"]+)['"]\s*(?:,\s*['"]([^'"]+)['"]\s*)?\)\s*\}\}"#)
+            .expect("doc reference pattern is valid")
+    });
+    doc_ref
+        .replace_all(markdown, |caps: &Captures| {
+            let (package, name) = match caps.get(2) {
+                Some(name) => (Some(&caps[1]), name.as_str()),
+                None => (None, &caps[1]),
+            };
+            let block = find_doc(manifest, package, name)
+                .filter(|(unique_id, _)| stack.len() < MAX_DOC_DEPTH && !stack.contains(&unique_id.as_str()))
+                .and_then(|(unique_id, doc)| Some((unique_id, doc.get("block_contents")?.as_str()?)))
+                .filter(|(_, contents)| contents.len() <= *budget);
+            let Some((unique_id, contents)) = block else {
+                return caps[0].to_string();
+            };
+            *budget -= contents.len();
+            stack.push(unique_id);
+            let expanded = expand_doc_refs(manifest, contents, stack, budget);
+            stack.pop();
+            expanded
+        })
+        .into_owned()
+}
+
+/// Markdown rendered to HTML with scripts, event handlers and other unsafe
+/// markup stripped.
+pub fn render_markdown(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, Parser::new_ext(markdown, options));
+    ammonia::clean(&rendered)
+}
+
+/// A description as sanitized HTML, with doc block references resolved.
+pub fn description_html(manifest: &Value, description: &str) -> String {
+    render_markdown(&resolve_doc_refs(manifest, description))
+}
+
+fn doc_block(manifest: &Value, unique_id: &str, doc: &Value) -> DocBlock {
+    let markdown = text(doc, "block_contents").unwrap_or_default();
+    DocBlock {
+        unique_id: unique_id.to_string(),
+        name: text(doc, "name").unwrap_or_default(),
+        package_name: text(doc, "package_name").unwrap_or_default(),
+        original_file_path: text(doc, "original_file_path"),
+        html: description_html(manifest, &markdown),
+        markdown,
+    }
+}
+
+/// Doc blocks of the project and its packages, ordered by unique_id.
+#[utoipa::path(
+    get,
+    path = "/docs",
+    tag = "docs",
+    responses(
+        (status = 200, body = Vec<DocBlockSummary>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_docs(Project(project): Project) -> Result<Json<Vec<DocBlockSummary>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let mut docs: Vec<DocBlockSummary> = manifest
+        .get("docs")
+        .and_then(|d| d.as_object())
+        .into_iter()
+        .flatten()
+        .map(|(unique_id, doc)| DocBlockSummary {
+            unique_id: unique_id.clone(),
+            name: text(doc, "name").unwrap_or_default(),
+            package_name: text(doc, "package_name").unwrap_or_default(),
+        })
+        .collect();
+    docs.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+    Ok(Json(docs))
+}
+
+/// A doc block as markdown and rendered HTML.
+#[utoipa::path(
+    get,
+    path = "/docs/{id}",
+    tag = "docs",
+    params(DocPath),
+    responses(
+        (status = 200, body = DocBlock),
+        (status = 404, description = "Unknown doc block", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_doc(
+    Project(project): Project,
+    Path(DocPath { id }): Path<DocPath>,
+) -> Result<Json<DocBlock>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, doc) =
+        find_doc(&manifest, None, &id).ok_or_else(|| (StatusCode::NOT_FOUND, format!("Doc block not found: {}", id)))?;
+    Ok(Json(doc_block(&manifest, unique_id, doc)))
+}
+
+/// The project overview: its own `__overview__` doc block, else dbt's default.
+#[utoipa::path(
+    get,
+    path = "/overview",
+    tag = "docs",
+    responses(
+        (status = 200, body = DocBlock),
+        (status = 404, description = "The manifest has no overview", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_overview(Project(project): Project) -> Result<Json<DocBlock>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, doc) = find_doc(&manifest, None, "__overview__")
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "The manifest has no overview doc block".to_string()))?;
+    Ok(Json(doc_block(&manifest, unique_id, doc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc(package: &str, name: &str, contents: &str) -> (String, Value) {
+        let unique_id = format!("doc.{}.{}", package, name);
+        let doc = json!({"unique_id": unique_id, "name": name, "package_name": package, "block_contents": contents});
+        (unique_id, doc)
+    }
+
+    fn manifest(docs: Vec<(String, Value)>) -> Value {
+        json!({"metadata": {"project_name": "shop"}, "docs": docs.into_iter().collect::<serde_json::Map<_, _>>()})
+    }
+
+    #[test]
+    fn resolve_doc_refs_prefers_the_projects_own_block() {
+        let manifest = manifest(vec![doc("dbt_utils", "status", "theirs"), doc("shop", "status", "ours")]);
+        assert_eq!(resolve_doc_refs(&manifest, "{{ doc('status') }}"), "ours");
+        assert_eq!(resolve_doc_refs(&manifest, "{{doc(\"dbt_utils\", \"status\")}}"), "theirs");
+        assert_eq!(resolve_doc_refs(&manifest, "{{ doc('doc.dbt_utils.status') }}"), "theirs");
+    }
+
+    #[test]
+    fn resolve_doc_refs_keeps_unknown_refs_as_written() {
+        let manifest = manifest(vec![doc("shop", "status", "ours")]);
+        let markdown = "{{ doc('missing') }} and {{ doc('other', 'status') }}";
+        assert_eq!(resolve_doc_refs(&manifest, markdown), markdown);
+    }
+
+    #[test]
+    fn resolve_doc_refs_expands_nested_refs_but_not_cycles() {
+        let manifest = manifest(vec![
+            doc("shop", "outer", "outer {{ doc('inner') }}"),
+            doc("shop", "inner", "inner {{ doc('outer') }} {{ doc('self') }}"),
+            doc("shop", "self", "self {{ doc('self') }}"),
+        ]);
+        assert_eq!(
+            resolve_doc_refs(&manifest, "{{ doc('outer') }}"),
+            "outer inner {{ doc('outer') }} self {{ doc('self') }}"
+        );
+    }
+
+    #[test]
+    fn resolve_doc_refs_caps_the_resolved_size() {
+        // Each level references the next ten times: 10^5 copies of the leaf if left unchecked
+        let fan_out = |next: &str| format!("{{{{ doc('{}') }}}}", next).repeat(10);
+        let manifest = manifest(vec![
+            doc("shop", "l0", &fan_out("l1")),
+            doc("shop", "l1", &fan_out("l2")),
+            doc("shop", "l2", &fan_out("l3")),
+            doc("shop", "l3", &fan_out("l4")),
+            doc("shop", "l4", &"x".repeat(4096)),
+        ]);
+        let resolved = resolve_doc_refs(&manifest, "{{ doc('l0') }}");
+        assert!(resolved.len() <= MAX_RESOLVED_BYTES * 2, "{} bytes", resolved.len());
+        assert!(resolved.contains("{{ doc('l4') }}"));
+    }
+}
@@ -0,0 +1,141 @@
+use axum::{extract::Query, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use log::warn;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::fs;
+
+use crate::config::ProjectConfig;
+use crate::projects::{load_manifest, Project};
+use crate::rbac::Access;
+use crate::store;
+
+/// One schema change recorded by the enrichment script on refresh.
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct DriftEvent {
+    pub detected_at: String,
+    pub unique_id: String,
+    pub name: Option<String>,
+    pub column: String,
+    /// `added`, `removed`, `retyped`, `declared_missing`,
+    /// `declared_type_mismatch` or `undocumented`.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declared_type: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DriftParams {
+    /// RFC 3339 timestamp; only events detected at or after it.
+    since: Option<String>,
+    /// Model name or unique_id.
+    model: Option<String>,
+    kind: Option<String>,
+    limit: Option<usize>,
+}
+
+/// All drift events in a project's drift log in the order they were recorded.
+/// The store imports them; `/drift` serves the imported copy.
+pub fn load_drift_events(project: &ProjectConfig) -> Vec<DriftEvent> {
+    let path = project.drift_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(event) => Some(event),
+            Err(err) => {
+                warn!("Skipping malformed drift event in {}: {}", path.display(), err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Drift events imported into the store, oldest first.
+fn stored_drift_events(project: &ProjectConfig) -> Result<Vec<DriftEvent>, String> {
+    store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT detected_at, unique_id, name, column_name, kind, previous_type, current_type, declared_type
+             FROM drift_events WHERE project = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![project.name], |row| {
+            Ok(DriftEvent {
+                detected_at: row.get(0)?,
+                unique_id: row.get(1)?,
+                name: row.get(2)?,
+                column: row.get(3)?,
+                kind: row.get(4)?,
+                previous_type: row.get(5)?,
+                current_type: row.get(6)?,
+                declared_type: row.get(7)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Drift events, newest first, of the nodes and columns the caller may see.
+#[utoipa::path(
+    get,
+    path = "/drift",
+    tag = "history",
+    params(DriftParams),
+    responses(
+        (status = 200, body = Vec<DriftEvent>),
+        (status = 400, description = "Invalid `since`", body = String),
+        (status = 503, description = "Manifest unavailable to check access", body = String),
+    )
+)]
+pub async fn get_drift(
+    Project(project): Project,
+    access: Access,
+    Query(params): Query<DriftParams>,
+) -> Result<Json<Vec<DriftEvent>>, (StatusCode, String)> {
+    let since = match &params.since {
+        Some(since) => Some(parse_time(since).ok_or_else(|| {
+            (StatusCode::BAD_REQUEST, format!("since must be an RFC 3339 timestamp, got '{}'", since))
+        })?),
+        None => None,
+    };
+    // Only restricted callers need the manifest, to check which nodes they see
+    let manifest = match access.is_restricted() {
+        true => Some(load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?),
+        false => None,
+    };
+    let hidden_columns = access.hidden_columns();
+
+    let mut events: Vec<DriftEvent> = stored_drift_events(project)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .into_iter()
+        .filter(|event| {
+            since.is_none_or(|since| parse_time(&event.detected_at).is_some_and(|t| t >= since))
+                && params.model.as_deref().is_none_or(|model| {
+                    event.unique_id == model || event.name.as_deref() == Some(model)
+                })
+                && params.kind.as_deref().is_none_or(|kind| event.kind == kind)
+                && manifest.as_ref().is_none_or(|manifest| access.can_see_id(manifest, &event.unique_id))
+                && hidden_columns
+                    .get(&event.unique_id)
+                    .is_none_or(|hidden| !hidden.contains(&event.column.to_lowercase()))
+        })
+        .collect();
+    events.reverse();
+    if let Some(limit) = params.limit {
+        events.truncate(limit);
+    }
+
+    Ok(Json(events))
+}
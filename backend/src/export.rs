@@ -0,0 +1,130 @@
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::config::ProjectConfig;
+use crate::governance::{self, Governance};
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, manifest_project_name};
+use crate::rbac::Access;
+
+/// Every configured project's resources with their columns and lineage.
+#[derive(Serialize)]
+pub struct CatalogExport {
+    pub exported_at: String,
+    pub projects: Vec<ProjectExport>,
+}
+
+#[derive(Serialize)]
+pub struct ProjectExport {
+    pub name: String,
+    pub dbt_project_name: Option<String>,
+    pub nodes: Vec<ExportedNode>,
+}
+
+#[derive(Serialize)]
+pub struct ExportedNode {
+    pub unique_id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub description: Option<String>,
+    pub materialized: Option<String>,
+    pub tags: Vec<String>,
+    pub governance: Governance,
+    pub columns: Vec<ExportedColumn>,
+    /// May name nodes of other projects (dbt mesh).
+    pub parents: Vec<String>,
+    pub children: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ExportedColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: Option<String>,
+    pub description: Option<String>,
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(String::from)
+}
+
+fn export_node(
+    graph: &CatalogGraph,
+    access: &Access,
+    hidden_columns: &HashSet<String>,
+    manifest: &Value,
+    unique_id: &str,
+    node: &Value,
+) -> ExportedNode {
+    let visible = |ids: &[String]| -> Vec<String> {
+        ids.iter().filter(|id| graph.nodes.get(*id).is_none_or(|n| access.can_see(&n.node))).cloned().collect()
+    };
+    let columns = node
+        .get("columns")
+        .and_then(|c| c.as_object())
+        .map_or(vec![], |columns| {
+            columns
+                .iter()
+                .filter(|(name, _)| !hidden_columns.contains(&name.to_lowercase()))
+                .map(|(name, col)| ExportedColumn {
+                    name: text(col, "name").unwrap_or_else(|| name.clone()),
+                    data_type: text(col, "type").or_else(|| text(col, "data_type")),
+                    description: text(col, "description").or_else(|| text(col, "comment")),
+                })
+                .collect()
+        });
+    ExportedNode {
+        unique_id: unique_id.to_string(),
+        name: text(node, "name").unwrap_or_default(),
+        resource_type: text(node, "resource_type").unwrap_or_default(),
+        description: text(node, "description"),
+        materialized: node.get("config").and_then(|c| text(c, "materialized")),
+        tags: node
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .map_or(vec![], |tags| tags.iter().filter_map(|t| t.as_str().map(String::from)).collect()),
+        governance: governance::extract(node, manifest),
+        columns,
+        parents: visible(graph.parents(unique_id)),
+        children: visible(graph.children(unique_id)),
+    }
+}
+
+/// Export `only` (or every project when `None`), nodes ordered by unique_id.
+/// Nodes and sensitive columns `access` may not see are left out.
+pub fn export_catalog(only: Option<&ProjectConfig>, access: &Access) -> CatalogExport {
+    let manifests = load_all_manifests();
+    // Lineage is resolved over every project even when exporting just one
+    let graph = CatalogGraph::build_full(&manifests);
+    let hidden_columns = access.hidden_columns();
+    let no_hidden_columns = HashSet::new();
+
+    let projects = manifests
+        .iter()
+        .filter(|(project, _)| only.is_none_or(|only| only.name == project.name))
+        .map(|(project, manifest)| {
+            let mut ids: Vec<&String> = graph
+                .nodes
+                .iter()
+                .filter(|(_, graph_node)| graph_node.project == project.name && access.can_see(&graph_node.node))
+                .map(|(id, _)| id)
+                .collect();
+            ids.sort();
+            ProjectExport {
+                name: project.name.clone(),
+                dbt_project_name: manifest_project_name(manifest).map(String::from),
+                nodes: ids
+                    .into_iter()
+                    .map(|id| {
+                        let hidden = hidden_columns.get(id).unwrap_or(&no_hidden_columns);
+                        export_node(&graph, access, hidden, manifest, id, &graph.nodes[id].node)
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    CatalogExport { exported_at: Utc::now().to_rfc3339(), projects }
+}
@@ -0,0 +1,538 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use log::error;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use crate::config::{self, ProjectConfig};
+use crate::dbt::find_model;
+use crate::projects::{load_all_manifests, load_manifest};
+use crate::rbac::Access;
+use crate::store;
+
+const STATUSES: &[&str] = &["draft", "approved", "deprecated"];
+
+/// A business term and the catalog entities it is linked to.
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct GlossaryTerm {
+    pub id: i64,
+    pub name: String,
+    pub definition: String,
+    pub synonyms: Vec<String>,
+    pub owner: Option<String>,
+    /// `draft`, `approved` or `deprecated`.
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub links: Vec<TermLink>,
+}
+
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct TermLink {
+    pub id: i64,
+    pub project: String,
+    /// Model, source or metric.
+    pub unique_id: String,
+    pub column: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TermInput {
+    name: String,
+    #[serde(default)]
+    definition: String,
+    #[serde(default)]
+    synonyms: Vec<String>,
+    owner: Option<String>,
+    /// `draft` (default), `approved` or `deprecated`.
+    #[serde(default = "default_status")]
+    status: String,
+}
+
+fn default_status() -> String {
+    "draft".to_string()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LinkInput {
+    /// Defaults to the default project.
+    project: Option<String>,
+    /// Model, source or metric name or unique_id.
+    target: String,
+    column: Option<String>,
+}
+
+/// A link the catalog proposes because the term (or a synonym) appears in a
+/// column's name or description.
+#[derive(Serialize, ToSchema)]
+pub struct LinkSuggestion {
+    project: String,
+    unique_id: String,
+    column: String,
+    matched: String,
+    /// `column_name` or `column_description`.
+    reason: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct TermPath {
+    /// Term name, case-insensitive.
+    term: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct LinkPath {
+    /// Term name, case-insensitive.
+    term: String,
+    /// Link id, as listed on the term.
+    link_id: i64,
+}
+
+type ApiError = (StatusCode, String);
+
+fn store_error(err: String) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, err)
+}
+
+fn query_terms(conn: &Connection, name: Option<&str>) -> rusqlite::Result<Vec<GlossaryTerm>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, definition, synonyms, owner, status, created_at, updated_at FROM glossary_terms
+         WHERE ?1 IS NULL OR name = ?1 COLLATE NOCASE ORDER BY name COLLATE NOCASE",
+    )?;
+    let mut terms = stmt
+        .query_map(params![name], |row| {
+            let synonyms: String = row.get(3)?;
+            Ok(GlossaryTerm {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                definition: row.get(2)?,
+                synonyms: serde_json::from_str(&synonyms).unwrap_or_default(),
+                owner: row.get(4)?,
+                status: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+                links: vec![],
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut links = conn.prepare(
+        "SELECT id, project, unique_id, column_name, created_at FROM glossary_links WHERE term_id = ?1 ORDER BY id",
+    )?;
+    for term in &mut terms {
+        term.links = links
+            .query_map(params![term.id], |row| {
+                let column: String = row.get(3)?;
+                Ok(TermLink {
+                    id: row.get(0)?,
+                    project: row.get(1)?,
+                    unique_id: row.get(2)?,
+                    column: (!column.is_empty()).then_some(column),
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+    }
+    Ok(terms)
+}
+
+/// Every glossary term with its links.
+pub fn load_terms() -> Result<Vec<GlossaryTerm>, String> {
+    store::with_conn(|conn| query_terms(conn, None))
+}
+
+fn load_term(name: &str) -> Result<GlossaryTerm, ApiError> {
+    store::with_conn(|conn| query_terms(conn, Some(name)))
+        .map_err(store_error)?
+        .pop()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Glossary term not found: {}", name)))
+}
+
+/// Drop the links to nodes and columns the caller may not see.
+fn retain_visible_links(access: &Access, terms: &mut [GlossaryTerm]) {
+    if !access.is_restricted() {
+        return;
+    }
+    let manifests: HashMap<String, Value> =
+        load_all_manifests().into_iter().map(|(project, manifest)| (project.name.clone(), manifest)).collect();
+    let hidden_columns = access.hidden_columns();
+    for term in terms {
+        term.links.retain(|link| {
+            manifests.get(&link.project).is_some_and(|manifest| access.can_see_id(manifest, &link.unique_id))
+                && link.column.as_ref().is_none_or(|column| {
+                    hidden_columns.get(&link.unique_id).is_none_or(|hidden| !hidden.contains(column))
+                })
+        });
+    }
+}
+
+/// A term with its links to what the caller may see.
+fn load_visible_term(access: &Access, name: &str) -> Result<GlossaryTerm, ApiError> {
+    let mut term = load_term(name)?;
+    retain_visible_links(access, std::slice::from_mut(&mut term));
+    Ok(term)
+}
+
+/// Names of the terms linked to a model, keyed by lowercased column name;
+/// links to the model itself are under the empty key.
+pub fn linked_terms(project: &ProjectConfig, unique_id: &str) -> HashMap<String, Vec<String>> {
+    store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT l.column_name, t.name FROM glossary_links l JOIN glossary_terms t ON t.id = l.term_id
+             WHERE l.project = ?1 AND l.unique_id = ?2 ORDER BY t.name COLLATE NOCASE",
+        )?;
+        let mut terms: HashMap<String, Vec<String>> = HashMap::new();
+        let rows = stmt.query_map(params![project.name, unique_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (column, name): (String, String) = row?;
+            terms.entry(column).or_default().push(name);
+        }
+        Ok(terms)
+    })
+    .unwrap_or_else(|e| {
+        error!("Failed to load glossary links of {}: {}", unique_id, e);
+        HashMap::new()
+    })
+}
+
+/// Trimmed, de-duplicated synonyms without the term's own name.
+fn clean_synonyms(input: &TermInput) -> Vec<String> {
+    let mut seen = HashSet::from([input.name.trim().to_lowercase()]);
+    input
+        .synonyms
+        .iter()
+        .map(|synonym| synonym.trim().to_string())
+        .filter(|synonym| !synonym.is_empty() && seen.insert(synonym.to_lowercase()))
+        .collect()
+}
+
+fn validate(input: &TermInput) -> Result<(), ApiError> {
+    if input.name.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Glossary term name must not be empty".to_string()));
+    }
+    if !STATUSES.contains(&input.status.as_str()) {
+        return Err((StatusCode::BAD_REQUEST, format!("status must be one of {}", STATUSES.join(", "))));
+    }
+    Ok(())
+}
+
+fn term_exists(name: &str, except_id: Option<i64>) -> Result<bool, ApiError> {
+    store::with_conn(|conn| {
+        conn.query_row(
+            "SELECT id FROM glossary_terms WHERE name = ?1 COLLATE NOCASE AND id IS NOT ?2",
+            params![name, except_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+    })
+    .map(|id| id.is_some())
+    .map_err(store_error)
+}
+
+/// All glossary terms with their links to what the caller may see.
+#[utoipa::path(get, path = "/glossary", tag = "glossary", responses((status = 200, body = Vec<GlossaryTerm>)))]
+pub async fn list_terms(access: Access) -> Result<Json<Vec<GlossaryTerm>>, ApiError> {
+    let mut terms = load_terms().map_err(store_error)?;
+    retain_visible_links(&access, &mut terms);
+    Ok(Json(terms))
+}
+
+#[utoipa::path(
+    get,
+    path = "/glossary/{term}",
+    tag = "glossary",
+    params(TermPath),
+    responses((status = 200, body = GlossaryTerm), (status = 404, description = "Unknown term", body = String))
+)]
+pub async fn get_term(access: Access, Path(TermPath { term }): Path<TermPath>) -> Result<Json<GlossaryTerm>, ApiError> {
+    load_visible_term(&access, &term).map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/glossary",
+    tag = "glossary",
+    request_body = TermInput,
+    responses(
+        (status = 201, body = GlossaryTerm),
+        (status = 400, description = "Invalid term", body = String),
+        (status = 409, description = "A term with that name exists", body = String),
+    )
+)]
+pub async fn create_term(Json(input): Json<TermInput>) -> Result<(StatusCode, Json<GlossaryTerm>), ApiError> {
+    validate(&input)?;
+    let name = input.name.trim();
+    if term_exists(name, None)? {
+        return Err((StatusCode::CONFLICT, format!("Glossary term already exists: {}", name)));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    store::with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO glossary_terms (name, definition, synonyms, owner, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+            params![
+                name,
+                input.definition,
+                serde_json::to_string(&clean_synonyms(&input)).unwrap_or_default(),
+                input.owner,
+                input.status,
+                now,
+            ],
+        )
+    })
+    .map_err(store_error)?;
+
+    Ok((StatusCode::CREATED, Json(load_term(name)?)))
+}
+
+/// Replace a term's fields; `name` may rename it.
+#[utoipa::path(
+    put,
+    path = "/glossary/{term}",
+    tag = "glossary",
+    params(TermPath),
+    request_body = TermInput,
+    responses(
+        (status = 200, body = GlossaryTerm),
+        (status = 400, description = "Invalid term", body = String),
+        (status = 404, description = "Unknown term", body = String),
+        (status = 409, description = "A term with the new name exists", body = String),
+    )
+)]
+pub async fn update_term(
+    access: Access,
+    Path(TermPath { term }): Path<TermPath>,
+    Json(input): Json<TermInput>,
+) -> Result<Json<GlossaryTerm>, ApiError> {
+    validate(&input)?;
+    let existing = load_term(&term)?;
+    let name = input.name.trim();
+    if term_exists(name, Some(existing.id))? {
+        return Err((StatusCode::CONFLICT, format!("Glossary term already exists: {}", name)));
+    }
+
+    store::with_conn(|conn| {
+        conn.execute(
+            "UPDATE glossary_terms SET name = ?2, definition = ?3, synonyms = ?4, owner = ?5, status = ?6, updated_at = ?7
+             WHERE id = ?1",
+            params![
+                existing.id,
+                name,
+                input.definition,
+                serde_json::to_string(&clean_synonyms(&input)).unwrap_or_default(),
+                input.owner,
+                input.status,
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )
+    })
+    .map_err(store_error)?;
+
+    load_visible_term(&access, name).map(Json)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/glossary/{term}",
+    tag = "glossary",
+    params(TermPath),
+    responses((status = 204, description = "Deleted with its links"), (status = 404, description = "Unknown term", body = String))
+)]
+pub async fn delete_term(Path(TermPath { term }): Path<TermPath>) -> Result<StatusCode, ApiError> {
+    let existing = load_term(&term)?;
+    store::with_conn(|conn| {
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM glossary_links WHERE term_id = ?1", params![existing.id])?;
+        tx.execute("DELETE FROM glossary_terms WHERE id = ?1", params![existing.id])?;
+        tx.commit()
+    })
+    .map_err(store_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A model (or other node), source or metric by name or unique_id.
+fn find_entity<'a>(manifest: &'a Value, target: &str) -> Option<&'a Value> {
+    find_model(manifest, target).or_else(|| {
+        let qualified = format!(".{}", target);
+        ["sources", "metrics"]
+            .iter()
+            .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+            .flat_map(|section| section.iter())
+            .find(|(unique_id, entity)| {
+                *unique_id == target
+                    || unique_id.ends_with(&qualified)
+                    || entity.get("name").and_then(|n| n.as_str()) == Some(target)
+            })
+            .map(|(_, entity)| entity)
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/glossary/{term}/links",
+    tag = "glossary",
+    params(TermPath),
+    request_body = LinkInput,
+    responses(
+        (status = 201, body = GlossaryTerm),
+        (status = 404, description = "Unknown term or project, or unknown or hidden entity or column", body = String),
+    )
+)]
+pub async fn add_link(
+    access: Access,
+    Path(TermPath { term }): Path<TermPath>,
+    Json(input): Json<LinkInput>,
+) -> Result<(StatusCode, Json<GlossaryTerm>), ApiError> {
+    let existing = load_term(&term)?;
+    let project = match &input.project {
+        Some(name) => config::get()
+            .project(name)
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Unknown project: {}", name)))?,
+        None => config::get().default_project(),
+    };
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let entity = find_entity(&manifest, &input.target)
+        .filter(|entity| access.can_see(entity))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Model, source or metric not found: {}", input.target)))?;
+    let unique_id = entity.get("unique_id").and_then(|u| u.as_str()).unwrap_or(&input.target);
+
+    let column = match input.column.as_deref() {
+        Some(column) => {
+            let known = entity
+                .get("columns")
+                .and_then(|c| c.as_object())
+                .is_some_and(|cols| cols.keys().any(|name| name.eq_ignore_ascii_case(column)));
+            let hidden = access
+                .hidden_columns()
+                .get(unique_id)
+                .is_some_and(|hidden| hidden.contains(&column.to_lowercase()));
+            if !known || hidden {
+                return Err((StatusCode::NOT_FOUND, format!("Column {} not found on {}", column, input.target)));
+            }
+            column.to_lowercase()
+        }
+        None => String::new(),
+    };
+
+    store::with_conn(|conn| {
+        conn.execute(
+            "INSERT OR IGNORE INTO glossary_links (term_id, project, unique_id, column_name, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![existing.id, project.name, unique_id, column, chrono::Utc::now().to_rfc3339()],
+        )
+    })
+    .map_err(store_error)?;
+
+    Ok((StatusCode::CREATED, Json(load_visible_term(&access, &existing.name)?)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/glossary/{term}/links/{link_id}",
+    tag = "glossary",
+    params(LinkPath),
+    responses((status = 204, description = "Deleted"), (status = 404, description = "Unknown term or link", body = String))
+)]
+pub async fn delete_link(Path(LinkPath { term, link_id }): Path<LinkPath>) -> Result<StatusCode, ApiError> {
+    let existing = load_term(&term)?;
+    let deleted = store::with_conn(|conn| {
+        conn.execute(
+            "DELETE FROM glossary_links WHERE id = ?1 AND term_id = ?2",
+            params![link_id, existing.id],
+        )
+    })
+    .map_err(store_error)?;
+    if deleted == 0 {
+        return Err((StatusCode::NOT_FOUND, format!("Link {} not found on {}", link_id, existing.name)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Matches a phrase as whole words, case-insensitively.
+fn phrase_pattern(phrase: &str) -> Option<Regex> {
+    let words: Vec<String> = phrase.split_whitespace().map(regex::escape).collect();
+    if words.is_empty() {
+        return None;
+    }
+    Regex::new(&format!(r"(?i)\b{}\b", words.join(r"[\s_-]+"))).ok()
+}
+
+/// Columns whose name or description mention the term or a synonym and
+/// aren't linked to it yet, across every project, among those the caller may see.
+#[utoipa::path(
+    get,
+    path = "/glossary/{term}/suggestions",
+    tag = "glossary",
+    params(TermPath),
+    responses((status = 200, body = Vec<LinkSuggestion>), (status = 404, description = "Unknown term", body = String))
+)]
+pub async fn get_suggestions(
+    access: Access,
+    Path(TermPath { term }): Path<TermPath>,
+) -> Result<Json<Vec<LinkSuggestion>>, ApiError> {
+    let existing = load_term(&term)?;
+    let linked: HashSet<(String, String, String)> = existing
+        .links
+        .iter()
+        .map(|link| (link.project.clone(), link.unique_id.clone(), link.column.clone().unwrap_or_default()))
+        .collect();
+    let phrases: Vec<(String, Regex, String)> = std::iter::once(&existing.name)
+        .chain(&existing.synonyms)
+        .filter_map(|phrase| {
+            // Column names spell the phrase in snake_case
+            let snake = phrase.split_whitespace().collect::<Vec<_>>().join("_").replace('-', "_").to_lowercase();
+            phrase_pattern(phrase).map(|pattern| (phrase.clone(), pattern, snake))
+        })
+        .collect();
+
+    let hidden_columns = access.hidden_columns();
+
+    let mut suggestions = vec![];
+    for (project, manifest) in load_all_manifests() {
+        let entities = ["nodes", "sources"]
+            .iter()
+            .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+            .flat_map(|section| section.iter())
+            .filter(|(_, node)| node.get("resource_type").and_then(|r| r.as_str()) != Some("test"))
+            .filter(|(_, node)| access.can_see(node));
+        for (unique_id, node) in entities {
+            let hidden = hidden_columns.get(unique_id);
+            let columns = node.get("columns").and_then(|c| c.as_object()).into_iter().flatten();
+            for (name, column) in columns {
+                let key = (project.name.clone(), unique_id.clone(), name.to_lowercase());
+                if linked.contains(&key) || hidden.is_some_and(|hidden| hidden.contains(&key.2)) {
+                    continue;
+                }
+                let description = column.get("description").and_then(|d| d.as_str()).unwrap_or_default();
+                let padded = format!("_{}_", name.to_lowercase());
+                let hit = phrases.iter().find_map(|(phrase, pattern, snake)| {
+                    if padded.contains(&format!("_{}_", snake)) {
+                        Some((phrase, "column_name"))
+                    } else if pattern.is_match(description) {
+                        Some((phrase, "column_description"))
+                    } else {
+                        None
+                    }
+                });
+                if let Some((phrase, reason)) = hit {
+                    suggestions.push(LinkSuggestion {
+                        project: project.name.clone(),
+                        unique_id: unique_id.clone(),
+                        column: name.clone(),
+                        matched: phrase.clone(),
+                        reason: reason.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(Json(suggestions))
+}
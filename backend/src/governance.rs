@@ -0,0 +1,191 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use serde_json::{Map, Value};
+
+use crate::config;
+use crate::projects::{load_manifest, Project};
+use crate::rbac::Access;
+
+/// Well-known governance fields pulled out of a node's `meta`.
+#[derive(Serialize, Default, Clone, Debug, ToSchema)]
+pub struct Governance {
+    pub owners: Vec<String>,
+    pub team: Option<String>,
+    pub domain: Option<String>,
+    pub tier: Option<String>,
+    pub pii: Option<bool>,
+    pub sla: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OwnedResource {
+    unique_id: String,
+    name: String,
+    resource_type: String,
+    /// Whether the lookup matched an owner or the team.
+    matched_as: String,
+    governance: Governance,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OwnerResources {
+    owner: String,
+    resources: Vec<OwnedResource>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct OwnerPath {
+    /// Owner name or email, or team.
+    owner: String,
+}
+
+/// `config.meta` merged with the node-level `meta` (which wins on conflicts).
+pub fn node_meta(node: &Value) -> Map<String, Value> {
+    let mut meta = node
+        .get("config")
+        .and_then(|c| c.get("meta"))
+        .and_then(|m| m.as_object())
+        .cloned()
+        .unwrap_or_default();
+    if let Some(own) = node.get("meta").and_then(|m| m.as_object()) {
+        meta.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    meta
+}
+
+/// First non-null value among `keys`, following dots into nested objects.
+pub fn lookup<'a>(meta: &'a Map<String, Value>, keys: &[String]) -> Option<&'a Value> {
+    keys.iter().find_map(|key| {
+        let mut parts = key.split('.');
+        let mut value = meta.get(parts.next()?)?;
+        for part in parts {
+            value = value.get(part)?;
+        }
+        (!value.is_null()).then_some(value)
+    })
+}
+
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        // Owner objects as written for exposures and groups: {name, email}
+        Value::Object(o) => o.get("name").or_else(|| o.get("email")).and_then(as_text),
+        _ => None,
+    }
+}
+
+fn as_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(as_text).collect(),
+        other => as_text(other).into_iter().collect(),
+    }
+}
+
+fn as_flag(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.to_lowercase().as_str() {
+            "true" | "yes" | "y" | "1" => Some(true),
+            "false" | "no" | "n" | "0" => Some(false),
+            _ => None,
+        },
+        Value::Number(n) => n.as_i64().map(|n| n != 0),
+        _ => None,
+    }
+}
+
+/// Extract the governance fields configured under `[governance]`. Models
+/// without an owner in meta inherit their group's owner; exposures use their
+/// declared `owner`.
+pub fn extract(node: &Value, manifest: &Value) -> Governance {
+    let keys = &config::get().governance;
+    let meta = node_meta(node);
+
+    let mut owners = lookup(&meta, &keys.owner_keys).map(as_list).unwrap_or_default();
+    if owners.is_empty() {
+        if let Some(owner) = node.get("owner") {
+            owners = as_list(owner);
+        }
+    }
+    if owners.is_empty() {
+        owners = group_owner(node, manifest).into_iter().collect();
+    }
+
+    Governance {
+        owners,
+        team: lookup(&meta, &keys.team_keys).and_then(as_text),
+        domain: lookup(&meta, &keys.domain_keys).and_then(as_text),
+        tier: lookup(&meta, &keys.tier_keys).and_then(as_text),
+        pii: lookup(&meta, &keys.pii_keys).and_then(as_flag),
+        sla: lookup(&meta, &keys.sla_keys).and_then(as_text),
+    }
+}
+
+fn group_owner(node: &Value, manifest: &Value) -> Option<String> {
+    let group = node
+        .get("group")
+        .or_else(|| node.get("config").and_then(|c| c.get("group")))
+        .and_then(|g| g.as_str())?;
+    manifest
+        .get("groups")?
+        .as_object()?
+        .values()
+        .find(|g| g.get("name").and_then(|n| n.as_str()) == Some(group))
+        .and_then(|g| g.get("owner"))
+        .and_then(as_text)
+}
+
+/// Everything a person or team owns: models, seeds, snapshots, sources and exposures.
+#[utoipa::path(
+    get,
+    path = "/owners/{owner}",
+    tag = "governance",
+    params(OwnerPath),
+    responses(
+        (status = 200, body = OwnerResources),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_owner_resources(
+    Project(project): Project,
+    access: Access,
+    Path(OwnerPath { owner }): Path<OwnerPath>,
+) -> Result<Json<OwnerResources>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let wanted = owner.to_lowercase();
+
+    let mut resources = vec![];
+    for section in ["nodes", "sources", "exposures"] {
+        let Some(entries) = manifest.get(section).and_then(|n| n.as_object()) else {
+            continue;
+        };
+        for (unique_id, node) in entries {
+            let resource_type = node.get("resource_type").and_then(|r| r.as_str()).unwrap_or_default();
+            if matches!(resource_type, "test" | "unit_test") || !access.can_see(node) {
+                continue;
+            }
+            let governance = extract(node, &manifest);
+            let matched_as = if governance.owners.iter().any(|o| o.to_lowercase() == wanted) {
+                "owner"
+            } else if governance.team.as_deref().map(str::to_lowercase) == Some(wanted.clone()) {
+                "team"
+            } else {
+                continue;
+            };
+            resources.push(OwnedResource {
+                unique_id: unique_id.clone(),
+                name: node.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+                resource_type: resource_type.to_string(),
+                matched_as: matched_as.to_string(),
+                governance,
+            });
+        }
+    }
+    resources.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+    Ok(Json(OwnerResources { owner, resources }))
+}
@@ -0,0 +1,211 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::config::ProjectConfig;
+use crate::mesh;
+use crate::projects::manifest_project_name;
+
+/// A manifest node together with the catalog project that owns it.
+pub struct GraphNode {
+    pub project: String,
+    pub node: Value,
+}
+
+/// Dependency graph over one or more project manifests. Nodes are keyed by
+/// dbt `unique_id`, so a cross-project `depends_on` entry (dbt mesh) lines up
+/// with the node defined in the upstream project's manifest.
+#[derive(Default)]
+pub struct CatalogGraph {
+    pub nodes: HashMap<String, GraphNode>,
+    parents: HashMap<String, Vec<String>>,
+    children: HashMap<String, Vec<String>>,
+}
+
+const GRAPH_SECTIONS: &[&str] = &["nodes", "sources"];
+const LINEAGE_SECTIONS: &[&str] = &["nodes", "sources", "semantic_models", "metrics", "saved_queries"];
+const FULL_SECTIONS: &[&str] = &["nodes", "sources", "exposures", "semantic_models", "metrics", "saved_queries"];
+
+impl CatalogGraph {
+    pub fn build(manifests: &[(&ProjectConfig, Value)]) -> Self {
+        Self::build_sections(manifests, GRAPH_SECTIONS, false)
+    }
+
+    /// Like `build`, plus the semantic layer (semantic models, metrics and
+    /// saved queries) so lineage shows which metrics a model feeds.
+    pub fn build_lineage(manifests: &[(&ProjectConfig, Value)]) -> Self {
+        Self::build_sections(manifests, LINEAGE_SECTIONS, false)
+    }
+
+    /// Like `build_lineage`, but exposures and tests are nodes of the graph too.
+    pub fn build_full(manifests: &[(&ProjectConfig, Value)]) -> Self {
+        Self::build_sections(manifests, FULL_SECTIONS, true)
+    }
+
+    fn build_sections(manifests: &[(&ProjectConfig, Value)], sections: &[&str], with_tests: bool) -> Self {
+        let mut graph = CatalogGraph::default();
+        // Tracks whether the stored copy came from the node's own project, so a
+        // public model re-published in a downstream manifest never shadows it.
+        let mut owned: HashSet<String> = HashSet::new();
+
+        for (project, manifest) in manifests {
+            let own_package = manifest_project_name(manifest);
+            for section in sections {
+                let Some(entries) = manifest.get(*section).and_then(|n| n.as_object()) else {
+                    continue;
+                };
+                for (unique_id, node) in entries {
+                    if is_test(node) && !with_tests {
+                        continue;
+                    }
+                    let is_owner = node.get("package_name").and_then(|p| p.as_str()) == own_package;
+                    if graph.nodes.contains_key(unique_id) && (owned.contains(unique_id) || !is_owner) {
+                        continue;
+                    }
+                    if is_owner {
+                        owned.insert(unique_id.clone());
+                    }
+                    graph.nodes.insert(
+                        unique_id.clone(),
+                        GraphNode { project: project.name.clone(), node: node.clone() },
+                    );
+                }
+            }
+        }
+
+        for (unique_id, graph_node) in &graph.nodes {
+            let parents = depends_on(&graph_node.node);
+            for parent in &parents {
+                graph.children.entry(parent.clone()).or_default().push(unique_id.clone());
+            }
+            graph.parents.insert(unique_id.clone(), parents);
+        }
+
+        graph
+    }
+
+    pub fn parents(&self, unique_id: &str) -> &[String] {
+        self.parents.get(unique_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn children(&self, unique_id: &str) -> &[String] {
+        self.children.get(unique_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// `unique_id` plus everything upstream of it.
+    pub fn ancestors(&self, unique_id: &str) -> HashSet<String> {
+        self.walk(unique_id, None, |id| self.parents(id))
+    }
+
+    /// `unique_id` plus everything downstream of it.
+    pub fn descendants(&self, unique_id: &str) -> HashSet<String> {
+        self.walk(unique_id, None, |id| self.children(id))
+    }
+
+    /// `unique_id` plus the nodes at most `depth` hops upstream of it.
+    pub fn ancestors_within(&self, unique_id: &str, depth: usize) -> HashSet<String> {
+        self.walk(unique_id, Some(depth), |id| self.parents(id))
+    }
+
+    /// `unique_id` plus the nodes at most `depth` hops downstream of it.
+    pub fn descendants_within(&self, unique_id: &str, depth: usize) -> HashSet<String> {
+        self.walk(unique_id, Some(depth), |id| self.children(id))
+    }
+
+    fn walk<'a>(
+        &'a self,
+        start: &str,
+        max_depth: Option<usize>,
+        next: impl Fn(&str) -> &'a [String],
+    ) -> HashSet<String> {
+        let mut seen = HashSet::from([start.to_string()]);
+        let mut queue = VecDeque::from([(start.to_string(), 0)]);
+        while let Some((id, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for neighbour in next(&id) {
+                if seen.insert(neighbour.clone()) {
+                    queue.push_back((neighbour.clone(), depth + 1));
+                }
+            }
+        }
+        seen
+    }
+
+    /// Node ids ordered so every node comes after its parents. Nodes caught
+    /// in a cycle (which dbt itself rejects) are appended at the end.
+    pub fn topological_order(&self) -> Vec<String> {
+        let mut pending: HashMap<&str, usize> = self
+            .nodes
+            .keys()
+            .map(|id| {
+                let known_parents = self.parents(id).iter().filter(|p| self.nodes.contains_key(*p)).count();
+                (id.as_str(), known_parents)
+            })
+            .collect();
+        let mut ready: Vec<&str> = pending.iter().filter(|(_, n)| **n == 0).map(|(id, _)| *id).collect();
+        ready.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = ready.pop() {
+            pending.remove(id);
+            order.push(id.to_string());
+            for child in self.children(id) {
+                if let Some(count) = pending.get_mut(child.as_str()) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(child.as_str());
+                    }
+                }
+            }
+        }
+        let mut leftover: Vec<String> = pending.keys().map(|id| id.to_string()).collect();
+        leftover.sort();
+        order.extend(leftover);
+        order
+    }
+
+    /// Resolve a model name or unique_id, preferring nodes of `project` and
+    /// falling back to public nodes of the other configured projects.
+    pub fn find(&self, name_or_id: &str, project: &str) -> Option<&str> {
+        if let Some((id, _)) = self.nodes.get_key_value(name_or_id) {
+            return Some(id.as_str());
+        }
+        let suffix = format!(".{}", name_or_id);
+        let mut matches: Vec<(&String, &GraphNode)> = self
+            .nodes
+            .iter()
+            .filter(|(id, graph_node)| {
+                graph_node.node.get("name").and_then(|n| n.as_str()) == Some(name_or_id) || id.ends_with(&suffix)
+            })
+            .filter(|(_, graph_node)| {
+                graph_node.project == project || mesh::access_of(&graph_node.node).as_deref() == Some("public")
+            })
+            .collect();
+        // Own project first, then models before other resource types, then by id for stable results
+        matches.sort_by_key(|(id, graph_node)| {
+            (
+                graph_node.project != project,
+                graph_node.node.get("resource_type").and_then(|r| r.as_str()) != Some("model"),
+                id.to_string(),
+            )
+        });
+        matches.first().map(|(id, _)| id.as_str())
+    }
+}
+
+fn is_test(node: &Value) -> bool {
+    matches!(
+        node.get("resource_type").and_then(|r| r.as_str()),
+        Some("test") | Some("unit_test")
+    )
+}
+
+fn depends_on(node: &Value) -> Vec<String> {
+    node.get("depends_on")
+        .and_then(|d| d.get("nodes"))
+        .and_then(|n| n.as_array())
+        .map_or(vec![], |nodes| {
+            nodes.iter().filter_map(|node| node.as_str().map(String::from)).collect()
+        })
+}
@@ -0,0 +1,382 @@
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{ComplexObject, Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema, SimpleObject};
+use axum::{response::Html, Json};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::config;
+use crate::graph::{CatalogGraph, GraphNode};
+use crate::projects::load_all_manifests;
+use crate::rbac::Access;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+/// Deep enough for several hops of `parents { parents { ... } }`, shallow
+/// enough that one query can't walk the whole graph.
+const MAX_QUERY_DEPTH: usize = 16;
+
+type CatalogSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+static SCHEMA: OnceLock<CatalogSchema> = OnceLock::new();
+
+/// Everything the resolvers of one request read from, built once per request.
+struct Catalog {
+    graph: CatalogGraph,
+    access: Access,
+    hidden_columns: HashMap<String, HashSet<String>>,
+}
+
+impl Catalog {
+    fn visible(&self, unique_id: &str) -> bool {
+        self.graph.nodes.get(unique_id).is_some_and(|n| self.access.can_see(&n.node))
+    }
+
+    /// Visible nodes among `ids`, sorted by unique_id.
+    fn nodes<'a>(&self, ids: impl IntoIterator<Item = &'a String>) -> Vec<Node> {
+        let mut ids: Vec<&String> = ids.into_iter().filter(|id| self.visible(id)).collect();
+        ids.sort();
+        ids.dedup();
+        ids.into_iter().map(|id| Node { unique_id: id.clone() }).collect()
+    }
+}
+
+fn catalog<'a>(ctx: &Context<'a>) -> &'a Catalog {
+    ctx.data_unchecked::<Catalog>()
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn resource_type(node: &Value) -> &str {
+    node.get("resource_type").and_then(|r| r.as_str()).unwrap_or_default()
+}
+
+fn is_test(node: &Value) -> bool {
+    matches!(resource_type(node), "test" | "unit_test")
+}
+
+/// The configured project `project` names, or the default one.
+fn project_name(project: Option<String>) -> async_graphql::Result<String> {
+    let config = config::get();
+    match project {
+        Some(name) if config.project(&name).is_none() => Err(format!("Unknown project: {}", name).into()),
+        Some(name) => Ok(name),
+        None => Ok(config.default_project().name.clone()),
+    }
+}
+
+/// Narrows a listing; every given field must match.
+#[derive(InputObject, Default)]
+struct NodeFilter {
+    /// Catalog project that owns the node.
+    project: Option<String>,
+    /// dbt resource type, e.g. `model`, `seed` or `snapshot`.
+    resource_type: Option<String>,
+    /// Case-insensitive substring of the node name.
+    name_contains: Option<String>,
+    tag: Option<String>,
+    package: Option<String>,
+    group: Option<String>,
+    access: Option<String>,
+    materialized: Option<String>,
+}
+
+impl NodeFilter {
+    fn matches(&self, graph_node: &GraphNode) -> bool {
+        let node = &graph_node.node;
+        let equals = |expected: &Option<String>, actual: Option<&Value>| {
+            expected.as_ref().is_none_or(|expected| actual.and_then(|v| v.as_str()) == Some(expected.as_str()))
+        };
+        let name_matches = self.name_contains.as_ref().is_none_or(|needle| {
+            text(node, "name").is_some_and(|name| name.to_lowercase().contains(&needle.to_lowercase()))
+        });
+        let tag_matches = self.tag.as_ref().is_none_or(|tag| {
+            node.get("tags").and_then(|t| t.as_array()).is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag)))
+        });
+
+        self.project.as_ref().is_none_or(|project| &graph_node.project == project)
+            && equals(&self.resource_type, node.get("resource_type"))
+            && name_matches
+            && tag_matches
+            && equals(&self.package, node.get("package_name"))
+            && equals(&self.group, node.get("group"))
+            && equals(&self.access, node.get("access"))
+            && equals(&self.materialized, node.get("config").and_then(|c| c.get("materialized")))
+    }
+}
+
+/// One page of a listing, ordered by unique_id.
+#[derive(SimpleObject)]
+struct NodePage {
+    /// Matching nodes across all pages.
+    total_count: usize,
+    has_next_page: bool,
+    nodes: Vec<Node>,
+}
+
+fn page(
+    ctx: &Context<'_>,
+    resource_types: &[&str],
+    filter: Option<NodeFilter>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> NodePage {
+    let catalog = catalog(ctx);
+    let filter = filter.unwrap_or_default();
+    let mut ids: Vec<&String> = catalog
+        .graph
+        .nodes
+        .iter()
+        .filter(|(_, graph_node)| {
+            (resource_types.is_empty() || resource_types.contains(&resource_type(&graph_node.node)))
+                && filter.matches(graph_node)
+                && catalog.access.can_see(&graph_node.node)
+        })
+        .map(|(id, _)| id)
+        .collect();
+    ids.sort();
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let offset = offset.unwrap_or(0);
+    NodePage {
+        total_count: ids.len(),
+        has_next_page: offset + limit < ids.len(),
+        nodes: ids.into_iter().skip(offset).take(limit).map(|id| Node { unique_id: id.clone() }).collect(),
+    }
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// A resource by unique_id, or by name, preferring `project` (default project when omitted).
+    async fn node(&self, ctx: &Context<'_>, id: String, project: Option<String>) -> async_graphql::Result<Option<Node>> {
+        let catalog = catalog(ctx);
+        let project = project_name(project)?;
+        Ok(catalog
+            .graph
+            .find(&id, &project)
+            .filter(|id| catalog.visible(id))
+            .map(|id| Node { unique_id: id.to_string() }))
+    }
+
+    /// Resources of any type; narrow with `filter.resourceType`.
+    async fn nodes(&self, ctx: &Context<'_>, filter: Option<NodeFilter>, limit: Option<usize>, offset: Option<usize>) -> NodePage {
+        page(ctx, &[], filter, limit, offset)
+    }
+
+    async fn models(&self, ctx: &Context<'_>, filter: Option<NodeFilter>, limit: Option<usize>, offset: Option<usize>) -> NodePage {
+        page(ctx, &["model"], filter, limit, offset)
+    }
+
+    async fn sources(&self, ctx: &Context<'_>, filter: Option<NodeFilter>, limit: Option<usize>, offset: Option<usize>) -> NodePage {
+        page(ctx, &["source"], filter, limit, offset)
+    }
+
+    async fn exposures(&self, ctx: &Context<'_>, filter: Option<NodeFilter>, limit: Option<usize>, offset: Option<usize>) -> NodePage {
+        page(ctx, &["exposure"], filter, limit, offset)
+    }
+
+    async fn metrics(&self, ctx: &Context<'_>, filter: Option<NodeFilter>, limit: Option<usize>, offset: Option<usize>) -> NodePage {
+        page(ctx, &["metric"], filter, limit, offset)
+    }
+
+    /// Data tests and unit tests.
+    async fn tests(&self, ctx: &Context<'_>, filter: Option<NodeFilter>, limit: Option<usize>, offset: Option<usize>) -> NodePage {
+        page(ctx, &["test", "unit_test"], filter, limit, offset)
+    }
+}
+
+/// A model, source, exposure, metric, test, seed or snapshot.
+pub struct Node {
+    unique_id: String,
+}
+
+impl Node {
+    fn graph_node<'a>(&self, ctx: &Context<'a>) -> &'a GraphNode {
+        &catalog(ctx).graph.nodes[&self.unique_id]
+    }
+
+    fn field(&self, ctx: &Context<'_>, key: &str) -> Option<String> {
+        text(&self.graph_node(ctx).node, key)
+    }
+}
+
+#[Object]
+impl Node {
+    async fn unique_id(&self) -> &str {
+        &self.unique_id
+    }
+
+    async fn name(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "name")
+    }
+
+    async fn resource_type(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "resource_type")
+    }
+
+    /// Catalog project that owns the node.
+    async fn project(&self, ctx: &Context<'_>) -> String {
+        self.graph_node(ctx).project.clone()
+    }
+
+    async fn package_name(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "package_name")
+    }
+
+    async fn description(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "description").filter(|d| !d.is_empty())
+    }
+
+    async fn database(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "database")
+    }
+
+    async fn schema(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "schema")
+    }
+
+    async fn materialized(&self, ctx: &Context<'_>) -> Option<String> {
+        self.graph_node(ctx).node.get("config").and_then(|c| text(c, "materialized"))
+    }
+
+    async fn tags(&self, ctx: &Context<'_>) -> Vec<String> {
+        self.graph_node(ctx)
+            .node
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .map_or(vec![], |tags| tags.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+    }
+
+    async fn access(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "access")
+    }
+
+    async fn group(&self, ctx: &Context<'_>) -> Option<String> {
+        self.field(ctx, "group")
+    }
+
+    /// `Redacted` for callers without access to raw SQL.
+    async fn raw_code(&self, ctx: &Context<'_>) -> Option<String> {
+        let code = self.field(ctx, "raw_code")?;
+        Some(if catalog(ctx).access.sees_raw_sql() { code } else { "Redacted".to_string() })
+    }
+
+    /// Documented columns; sensitive ones the caller may not read are redacted.
+    async fn columns(&self, ctx: &Context<'_>) -> Vec<Column> {
+        let hidden = catalog(ctx).hidden_columns.get(&self.unique_id);
+        let Some(columns) = self.graph_node(ctx).node.get("columns").and_then(|c| c.as_object()) else {
+            return vec![];
+        };
+        columns
+            .iter()
+            .map(|(key, col)| {
+                let name = text(col, "name").unwrap_or_else(|| key.clone());
+                let redacted = hidden.is_some_and(|hidden| hidden.contains(&name.to_lowercase()));
+                Column {
+                    // Warehouse type from the enriched manifest, else the YAML one
+                    data_type: text(col, "type").or_else(|| text(col, "data_type")).filter(|_| !redacted),
+                    description: text(col, "description")
+                        .filter(|d| !d.is_empty())
+                        .or_else(|| text(col, "comment"))
+                        .filter(|_| !redacted),
+                    redacted,
+                    name,
+                    node_id: self.unique_id.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Tests that depend on the node.
+    async fn tests(&self, ctx: &Context<'_>) -> Vec<Node> {
+        let catalog = catalog(ctx);
+        catalog.nodes(catalog.graph.children(&self.unique_id).iter().filter(|id| {
+            catalog.graph.nodes.get(*id).is_some_and(|n| is_test(&n.node))
+        }))
+    }
+
+    /// Direct upstream nodes.
+    async fn parents(&self, ctx: &Context<'_>) -> Vec<Node> {
+        let catalog = catalog(ctx);
+        catalog.nodes(catalog.graph.parents(&self.unique_id))
+    }
+
+    /// Direct downstream nodes, without tests.
+    async fn children(&self, ctx: &Context<'_>) -> Vec<Node> {
+        let catalog = catalog(ctx);
+        catalog.nodes(catalog.graph.children(&self.unique_id).iter().filter(|id| {
+            catalog.graph.nodes.get(*id).is_some_and(|n| !is_test(&n.node))
+        }))
+    }
+
+    /// Nodes at most `depth` hops upstream, as a flat list.
+    async fn upstream(&self, ctx: &Context<'_>, #[graphql(default = 1)] depth: usize) -> Vec<Node> {
+        let catalog = catalog(ctx);
+        let ids = catalog.graph.ancestors_within(&self.unique_id, depth);
+        catalog.nodes(ids.iter().filter(|id| **id != self.unique_id))
+    }
+
+    /// Nodes at most `depth` hops downstream, as a flat list, without tests.
+    async fn downstream(&self, ctx: &Context<'_>, #[graphql(default = 1)] depth: usize) -> Vec<Node> {
+        let catalog = catalog(ctx);
+        let ids = catalog.graph.descendants_within(&self.unique_id, depth);
+        catalog.nodes(ids.iter().filter(|id| {
+            **id != self.unique_id && catalog.graph.nodes.get(*id).is_some_and(|n| !is_test(&n.node))
+        }))
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+struct Column {
+    name: String,
+    data_type: Option<String>,
+    description: Option<String>,
+    /// Sensitive column the caller may not read; type and description are withheld.
+    redacted: bool,
+    #[graphql(skip)]
+    node_id: String,
+}
+
+#[ComplexObject]
+impl Column {
+    /// Tests attached to this column.
+    async fn tests(&self, ctx: &Context<'_>) -> Vec<Node> {
+        let catalog = catalog(ctx);
+        catalog.nodes(catalog.graph.children(&self.node_id).iter().filter(|id| {
+            catalog.graph.nodes.get(*id).is_some_and(|n| {
+                is_test(&n.node)
+                    && text(&n.node, "column_name").is_some_and(|col| col.eq_ignore_ascii_case(&self.name))
+                    && text(&n.node, "attached_node").is_none_or(|attached| attached == self.node_id)
+            })
+        }))
+    }
+}
+
+fn schema() -> &'static CatalogSchema {
+    SCHEMA.get_or_init(|| {
+        Schema::build(Query, EmptyMutation, EmptySubscription)
+            .limit_depth(MAX_QUERY_DEPTH)
+            .finish()
+    })
+}
+
+/// Execute a GraphQL query over the catalog graph of every configured
+/// project. Nodes the caller may not see are left out.
+pub async fn graphql(access: Access, Json(request): Json<async_graphql::Request>) -> Json<async_graphql::Response> {
+    let catalog = Catalog {
+        graph: CatalogGraph::build_full(&load_all_manifests()),
+        hidden_columns: access.hidden_columns(),
+        access,
+    };
+    Json(schema().execute(request.data(catalog)).await)
+}
+
+/// GraphiQL, for exploring the schema in a browser.
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -0,0 +1,260 @@
+use axum::{http::StatusCode, Json};
+use log::error;
+use rusqlite::params;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::config::{self, ProjectConfig};
+use crate::coverage::{model_coverage, ModelCoverage};
+use crate::governance;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+use crate::store;
+
+/// Each component is 0-100, or `None` when there is nothing to judge it by
+/// (no runs recorded, no upstream source freshness); missing components are
+/// left out of the weighted score.
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct HealthComponents {
+    /// Model and column descriptions.
+    pub documentation: Option<f64>,
+    /// Columns with at least one test; column-less models count when tested at all.
+    pub tests: Option<f64>,
+    /// Successful runs among the latest `health.recent_runs`.
+    pub runs: Option<f64>,
+    /// Fresh upstream sources, warnings counting half.
+    pub freshness: Option<f64>,
+    pub ownership: Option<f64>,
+    pub contract: Option<f64>,
+}
+
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct HealthScore {
+    /// Weighted mean of the components, 0-100.
+    pub score: f64,
+    pub components: HealthComponents,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ModelHealth {
+    pub unique_id: String,
+    pub name: String,
+    /// Directory of the model's file, e.g. `models/staging`.
+    pub folder: String,
+    pub group: Option<String>,
+    pub health: HealthScore,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthAggregate {
+    pub name: String,
+    pub models: usize,
+    /// Mean score of the models.
+    pub score: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthReport {
+    /// Lowest score first.
+    pub models: Vec<ModelHealth>,
+    pub folders: Vec<HealthAggregate>,
+    pub groups: Vec<HealthAggregate>,
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    round(part as f64 * 100.0 / total as f64)
+}
+
+/// What scoring needs besides the model itself, loaded once per request.
+pub struct HealthInputs<'a> {
+    /// Upstream sources may live in other projects.
+    graph: &'a CatalogGraph,
+    coverage: HashMap<String, ModelCoverage>,
+    /// Latest run statuses per node, newest first.
+    runs: HashMap<String, Vec<String>>,
+    /// Latest freshness status per source, from any project.
+    freshness: HashMap<String, String>,
+}
+
+impl<'a> HealthInputs<'a> {
+    /// Runs are loaded for `only` when given, else for every node of the project.
+    pub fn load(project: &ProjectConfig, manifest: &Value, graph: &'a CatalogGraph, only: Option<&str>) -> Result<Self, String> {
+        let recent_runs = config::get().health.recent_runs;
+        let (runs, freshness) = store::with_conn(|conn| {
+            let mut runs: HashMap<String, Vec<String>> = HashMap::new();
+            let mut stmt = conn.prepare(
+                "SELECT unique_id, status FROM run_results
+                 WHERE project = ?1 AND status IS NOT NULL AND (?2 IS NULL OR unique_id = ?2) ORDER BY id DESC",
+            )?;
+            let rows = stmt.query_map(params![project.name, only], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+            for row in rows {
+                let (unique_id, status) = row?;
+                let statuses = runs.entry(unique_id).or_default();
+                if statuses.len() < recent_runs {
+                    statuses.push(status);
+                }
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT unique_id, status FROM source_freshness
+                 WHERE status IS NOT NULL AND id IN (SELECT max(id) FROM source_freshness GROUP BY project, unique_id)",
+            )?;
+            let freshness = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+            Ok((runs, freshness))
+        })?;
+
+        Ok(HealthInputs {
+            graph,
+            coverage: model_coverage(manifest).into_iter().map(|c| (c.unique_id.clone(), c)).collect(),
+            runs,
+            freshness,
+        })
+    }
+
+    pub fn score(&self, manifest: &Value, unique_id: &str, node: &Value) -> HealthScore {
+        let coverage = self.coverage.get(unique_id);
+        let documentation = coverage.map(|c| ratio(c.described as usize + c.documented_columns, 1 + c.columns));
+        let tests = coverage.map(|c| match c.columns {
+            0 => if c.tests > 0 { 100.0 } else { 0.0 },
+            columns => ratio(c.tested_columns, columns),
+        });
+
+        let runs = self.runs.get(unique_id).filter(|s| !s.is_empty()).map(|statuses| {
+            ratio(statuses.iter().filter(|s| matches!(s.as_str(), "success" | "pass")).count(), statuses.len())
+        });
+
+        let source_scores: Vec<f64> = self
+            .graph
+            .ancestors(unique_id)
+            .iter()
+            .filter_map(|id| self.freshness.get(id))
+            .map(|status| match status.as_str() {
+                "pass" => 1.0,
+                "warn" => 0.5,
+                _ => 0.0,
+            })
+            .collect();
+        let freshness = (!source_scores.is_empty())
+            .then(|| round(source_scores.iter().sum::<f64>() * 100.0 / source_scores.len() as f64));
+
+        let owned = !governance::extract(node, manifest).owners.is_empty();
+        let enforced = node
+            .get("config")
+            .and_then(|c| c.get("contract"))
+            .and_then(|c| c.get("enforced"))
+            .and_then(|e| e.as_bool())
+            .unwrap_or(false);
+
+        let components = HealthComponents {
+            documentation,
+            tests,
+            runs,
+            freshness,
+            ownership: Some(if owned { 100.0 } else { 0.0 }),
+            contract: Some(if enforced { 100.0 } else { 0.0 }),
+        };
+        HealthScore { score: weighted(&components), components }
+    }
+}
+
+fn weighted(components: &HealthComponents) -> f64 {
+    let weights = &config::get().health.weights;
+    let parts = [
+        (components.documentation, weights.documentation),
+        (components.tests, weights.tests),
+        (components.runs, weights.runs),
+        (components.freshness, weights.freshness),
+        (components.ownership, weights.ownership),
+        (components.contract, weights.contract),
+    ];
+    let (total, weight) = parts
+        .iter()
+        .filter_map(|(value, weight)| value.map(|value| (value * weight, *weight)))
+        .fold((0.0, 0.0), |(total, weights), (value, weight)| (total + value, weights + weight));
+    if weight == 0.0 {
+        0.0
+    } else {
+        round(total / weight)
+    }
+}
+
+/// Score of one model, logged and skipped when the store is unavailable.
+pub fn model_health(project: &ProjectConfig, manifest: &Value, graph: &CatalogGraph, model: &Value) -> Option<HealthScore> {
+    let unique_id = model.get("unique_id").and_then(|u| u.as_str())?;
+    match HealthInputs::load(project, manifest, graph, Some(unique_id)) {
+        Ok(inputs) => Some(inputs.score(manifest, unique_id, model)),
+        Err(err) => {
+            error!("Failed to load health inputs for project '{}': {}", project.name, err);
+            None
+        }
+    }
+}
+
+fn aggregate(groups: BTreeMap<String, Vec<f64>>) -> Vec<HealthAggregate> {
+    groups
+        .into_iter()
+        .map(|(name, scores)| HealthAggregate {
+            name,
+            models: scores.len(),
+            score: round(scores.iter().sum::<f64>() / scores.len() as f64),
+        })
+        .collect()
+}
+
+/// Health score of every model, with the mean per folder and per group.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, body = HealthReport),
+        (status = 500, description = "Store error", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_health(Project(project): Project, access: Access) -> Result<Json<HealthReport>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let graph = CatalogGraph::build(&load_all_manifests());
+    let inputs = HealthInputs::load(project, &manifest, &graph, None).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let mut models: Vec<ModelHealth> = manifest
+        .get("nodes")
+        .and_then(|n| n.as_object())
+        .into_iter()
+        .flatten()
+        .filter(|(_, node)| node.get("resource_type").and_then(|r| r.as_str()) == Some("model"))
+        .filter(|(_, node)| access.can_see(node))
+        .map(|(unique_id, node)| {
+            let text = |value: Option<&Value>| value.and_then(|v| v.as_str()).map(String::from);
+            let path = text(node.get("original_file_path")).unwrap_or_default();
+            ModelHealth {
+                unique_id: unique_id.clone(),
+                name: text(node.get("name")).unwrap_or_default(),
+                folder: path.rsplit_once('/').map_or(String::new(), |(dir, _)| dir.to_string()),
+                group: text(node.get("group")).or_else(|| text(node.get("config").and_then(|c| c.get("group")))),
+                health: inputs.score(&manifest, unique_id, node),
+            }
+        })
+        .collect();
+    models.sort_by(|a, b| a.health.score.total_cmp(&b.health.score).then_with(|| a.unique_id.cmp(&b.unique_id)));
+
+    let mut folders: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    let mut groups: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for model in &models {
+        folders.entry(model.folder.clone()).or_default().push(model.health.score);
+        if let Some(group) = &model.group {
+            groups.entry(group.clone()).or_default().push(model.health.score);
+        }
+    }
+
+    Ok(Json(HealthReport { models, folders: aggregate(folders), groups: aggregate(groups) }))
+}
@@ -0,0 +1,214 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::config::ProjectConfig;
+use crate::dbt::{find_model, ModelPath};
+use crate::projects::{load_manifest, Project};
+use crate::rbac::Access;
+use crate::store;
+
+#[derive(Serialize, ToSchema)]
+pub struct SnapshotSummary {
+    id: i64,
+    invocation_id: String,
+    generated_at: Option<String>,
+    dbt_version: Option<String>,
+    captured_at: String,
+    /// Nodes and sources in the snapshot the caller may see.
+    node_count: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RunResult {
+    invocation_id: String,
+    command: Option<String>,
+    generated_at: Option<String>,
+    status: Option<String>,
+    execution_time: Option<f64>,
+    failures: Option<i64>,
+    message: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SourceFreshness {
+    unique_id: String,
+    status: Option<String>,
+    max_loaded_at: Option<String>,
+    snapshotted_at: Option<String>,
+    age_seconds: Option<f64>,
+    generated_at: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryParams {
+    /// Most recent runs to return; all when omitted.
+    limit: Option<usize>,
+}
+
+/// Maps a row selected as `invocation_id, command, generated_at, status,
+/// execution_time, failures, message`.
+fn run_result(row: &Row) -> rusqlite::Result<RunResult> {
+    Ok(RunResult {
+        invocation_id: row.get(0)?,
+        command: row.get(1)?,
+        generated_at: row.get(2)?,
+        status: row.get(3)?,
+        execution_time: row.get(4)?,
+        failures: row.get(5)?,
+        message: row.get(6)?,
+    })
+}
+
+fn store_error(err: String) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err)
+}
+
+/// Nodes and sources of a stored manifest the caller may see.
+fn visible_node_count(access: &Access, manifest: &str) -> i64 {
+    let manifest: serde_json::Value = serde_json::from_str(manifest).unwrap_or_default();
+    ["nodes", "sources"]
+        .iter()
+        .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+        .flat_map(|section| section.values())
+        .filter(|node| access.can_see(node))
+        .count() as i64
+}
+
+/// Stored manifest snapshots, newest first.
+#[utoipa::path(get, path = "/snapshots", tag = "history", responses((status = 200, body = Vec<SnapshotSummary>)))]
+pub async fn list_snapshots(
+    Project(project): Project,
+    access: Access,
+) -> Result<Json<Vec<SnapshotSummary>>, (StatusCode, String)> {
+    store::with_conn(|conn| {
+        // Restricted callers get counts of what they may see, from the stored manifest
+        let mut stmt = conn.prepare(
+            "SELECT id, invocation_id, generated_at, dbt_version, captured_at, node_count,
+                    CASE WHEN ?2 THEN manifest END
+             FROM manifest_snapshots WHERE project = ?1 ORDER BY id DESC",
+        )?;
+        let rows = stmt.query_map(params![project.name, access.is_restricted()], |row| {
+            let manifest: Option<String> = row.get(6)?;
+            Ok(SnapshotSummary {
+                id: row.get(0)?,
+                invocation_id: row.get(1)?,
+                generated_at: row.get(2)?,
+                dbt_version: row.get(3)?,
+                captured_at: row.get(4)?,
+                node_count: match manifest {
+                    Some(manifest) => visible_node_count(&access, &manifest),
+                    None => row.get(5)?,
+                },
+            })
+        })?;
+        rows.collect()
+    })
+    .map(Json)
+    .map_err(store_error)
+}
+
+/// Run results of a model (or a test, by unique_id) across dbt invocations, newest first.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/runs",
+    tag = "history",
+    params(ModelPath, HistoryParams),
+    responses(
+        (status = 200, body = Vec<RunResult>),
+        (status = 404, description = "Hidden model", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_model_runs(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+    Query(params): Query<HistoryParams>,
+) -> Result<Json<Vec<RunResult>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let unique_id = find_model(&manifest, &id)
+        .and_then(|model| model.get("unique_id"))
+        .and_then(|u| u.as_str())
+        .unwrap_or(&id)
+        .to_string();
+    if !access.can_see_id(&manifest, &unique_id) {
+        return Err((StatusCode::NOT_FOUND, format!("Model not found: {}", id)));
+    }
+    let limit = params.limit.map_or(-1, |limit| limit as i64);
+
+    store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT invocation_id, command, generated_at, status, execution_time, failures, message
+             FROM run_results WHERE project = ?1 AND unique_id = ?2 ORDER BY id DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![project.name, unique_id, limit], run_result)?;
+        rows.collect()
+    })
+    .map(Json)
+    .map_err(store_error)
+}
+
+/// The most recent stored result of a node, e.g. a test or unit test.
+pub fn latest_run(project: &ProjectConfig, unique_id: &str) -> Result<Option<RunResult>, String> {
+    store::with_conn(|conn| {
+        conn.query_row(
+            "SELECT invocation_id, command, generated_at, status, execution_time, failures, message
+             FROM run_results WHERE project = ?1 AND unique_id = ?2 ORDER BY id DESC LIMIT 1",
+            params![project.name, unique_id],
+            run_result,
+        )
+        .optional()
+    })
+}
+
+/// Latest freshness check of every source the caller may see.
+#[utoipa::path(
+    get,
+    path = "/freshness",
+    tag = "history",
+    responses(
+        (status = 200, body = Vec<SourceFreshness>),
+        (status = 503, description = "Manifest unavailable to check access", body = String),
+    )
+)]
+pub async fn get_freshness(
+    Project(project): Project,
+    access: Access,
+) -> Result<Json<Vec<SourceFreshness>>, (StatusCode, String)> {
+    // Only restricted callers need the manifest, to check which sources they see
+    let manifest = match access.is_restricted() {
+        true => Some(load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?),
+        false => None,
+    };
+    let mut checks: Vec<SourceFreshness> = store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT unique_id, status, max_loaded_at, snapshotted_at, age_seconds, generated_at
+             FROM source_freshness
+             WHERE id IN (SELECT max(id) FROM source_freshness WHERE project = ?1 GROUP BY unique_id)
+             ORDER BY unique_id",
+        )?;
+        let rows = stmt.query_map(params![project.name], |row| {
+            Ok(SourceFreshness {
+                unique_id: row.get(0)?,
+                status: row.get(1)?,
+                max_loaded_at: row.get(2)?,
+                snapshotted_at: row.get(3)?,
+                age_seconds: row.get(4)?,
+                generated_at: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    })
+    .map_err(store_error)?;
+    if let Some(manifest) = &manifest {
+        checks.retain(|check| access.can_see_id(manifest, &check.unique_id));
+    }
+    Ok(Json(checks))
+}
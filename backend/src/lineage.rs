@@ -1,33 +1,39 @@
-use std::process::{Command, Output};
-use std::path::Path;
 use axum::{extract::Path as AxumPath, Json};
 use serde::{Deserialize, Serialize};
-use log::{info, error};
-use crate::dbt::{run_dbt_command, clean_dbt_output, get_model_details};
+use utoipa::{IntoParams, ToSchema};
+use log::error;
+use std::collections::HashMap;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, Project};
+use crate::rbac::Access;
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct Dependencies {
-    nodes: Vec<String>,
+    pub nodes: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ModelMetadata {
-    name: String,
-    schema: String,
-    materialization: Option<String>,
-    tags: Vec<String>,
-    depends_on: Dependencies,
+    pub name: String,
+    pub unique_id: String,
+    pub project: String,
+    /// `model`, `source`, `semantic_model`, `metric`, ...
+    pub resource_type: String,
+    pub schema: String,
+    pub materialization: Option<String>,
+    pub tags: Vec<String>,
+    pub depends_on: Dependencies,
+    /// Placeholder for a node the caller may not see; keeps the path connected.
+    pub redacted: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct Lineage {
-    models: Vec<ModelMetadata>,
+    pub models: Vec<ModelMetadata>,
 }
 
-const DBT_PROJECT_DIR: &str = "/backend/dbt_project";
-
-/// Helper function to run a DBT command and clean the output
+// Helper function to run a DBT command and clean the output
 // fn run_dbt_command(dbt_project_dir: &str, args: &[&str]) -> Result<Output, String> {
 //     if !Path::new(dbt_project_dir).exists() {
 //         error!("DBT project directory does not exist: {}", dbt_project_dir);
@@ -64,51 +70,117 @@ const DBT_PROJECT_DIR: &str = "/backend/dbt_project";
 //     Ok(output)
 // }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct LineagePath {
+    /// Model to start from (name or unique_id).
+    start: String,
+    /// Model to end at (name or unique_id).
+    end: String,
+}
+
+/// Nodes downstream of `start` and upstream of `end` (dbt's `start+,+end`),
+/// resolved over every configured project so the path can cross into
+/// public models of other projects. Nodes the caller may not see are
+/// replaced by redacted placeholders.
+#[utoipa::path(
+    get,
+    path = "/lineage/{start}/{end}",
+    tag = "lineage",
+    params(LineagePath),
+    responses((status = 200, description = "Empty when either endpoint is unknown or hidden", body = Lineage))
+)]
 pub async fn get_lineage(
-    AxumPath((start_model, end_model)): AxumPath<(String, String)>,
+    Project(project): Project,
+    access: Access,
+    AxumPath(LineagePath { start: start_model, end: end_model }): AxumPath<LineagePath>,
 ) -> Json<Lineage> {
-    let dbt_project_dir = DBT_PROJECT_DIR;
-    let lineage_query = format!("{}+,+{}", start_model, end_model);
-    let args = &["ls", "--output", "json", "--models", &lineage_query];
-
-    // Fetch lineage models using DBT
-    let lineage_models: Vec<ModelMetadata> = match run_dbt_command(dbt_project_dir, args) {
-        Ok(cleaned_output) => {
-            match serde_json::from_str::<Vec<serde_json::Value>>(&cleaned_output) {
-                Ok(models) => models
-                    .into_iter()
-                    .filter_map(|model| {
-                        let name = model.get("name")?.as_str()?.to_string();
-                        let depends_on = model
-                            .get("depends_on")
-                            .and_then(|d| d.get("nodes"))
-                            .and_then(|n| n.as_array())
-                            .map_or(vec![], |nodes| {
-                                nodes.iter().filter_map(|node| node.as_str().map(String::from)).collect()
-                            });
-
-                        Some(ModelMetadata {
-                            name,
-                            schema: String::new(),        // Empty as metadata is separate
-                            materialization: None,        // Empty as metadata is separate
-                            tags: vec![],                 // Empty as metadata is separate
-                            depends_on: Dependencies { nodes: depends_on },
-                        })
-                    })
-                    .collect(),
-                Err(err) => {
-                    error!("Failed to parse DBT JSON output for lineage models: {}", err);
-                    vec![]
-                }
-            }
-        }
-        Err(err) => {
-            error!("Failed to run DBT command for lineage: {}", err);
-            vec![]
+    let graph = CatalogGraph::build_lineage(&load_all_manifests());
+    let visible = |id: &str| graph.nodes.get(id).is_some_and(|n| access.can_see(&n.node));
+
+    let (Some(start_id), Some(end_id)) = (
+        graph.find(&start_model, &project.name).filter(|id| visible(id)),
+        graph.find(&end_model, &project.name).filter(|id| visible(id)),
+    ) else {
+        error!("Lineage endpoints not found in project '{}': {} -> {}", project.name, start_model, end_model);
+        return Json(Lineage { models: vec![] });
+    };
+
+    let upstream_of_end = graph.ancestors(end_id);
+    let lineage_ids: Vec<&String> = graph
+        .descendants(start_id)
+        .iter()
+        .filter_map(|id| upstream_of_end.get(id))
+        .collect();
+
+    Json(lineage_of(&graph, &access, lineage_ids))
+}
+
+/// Everything upstream and downstream of `unique_id`, including itself.
+pub fn model_lineage(graph: &CatalogGraph, access: &Access, unique_id: &str) -> Lineage {
+    let upstream = graph.ancestors(unique_id);
+    let downstream = graph.descendants(unique_id);
+    lineage_of(graph, access, upstream.iter().chain(downstream.iter()).collect())
+}
+
+/// The lineage entries of `ids`, sorted. Nodes the caller may not see are
+/// replaced by redacted placeholders.
+fn lineage_of(graph: &CatalogGraph, access: &Access, mut lineage_ids: Vec<&String>) -> Lineage {
+    let visible = |id: &str| graph.nodes.get(id).is_some_and(|n| access.can_see(&n.node));
+    lineage_ids.sort();
+    lineage_ids.dedup();
+
+    // Stable within the response so placeholders still connect to each other
+    let mut placeholders: HashMap<String, String> = HashMap::new();
+    let mut display_id = |id: &String| -> String {
+        if visible(id) {
+            return id.clone();
         }
+        let next = format!("redacted.{}", placeholders.len() + 1);
+        placeholders.entry(id.clone()).or_insert(next).clone()
     };
 
-    Json(Lineage { models: lineage_models })
+    let lineage_models = lineage_ids
+        .into_iter()
+        .filter_map(|id| {
+            let graph_node = graph.nodes.get(id)?;
+            let node = &graph_node.node;
+            let depends_on = Dependencies { nodes: graph.parents(id).iter().map(&mut display_id).collect() };
+            if !visible(id) {
+                return Some(ModelMetadata {
+                    name: "Redacted".to_string(),
+                    unique_id: display_id(id),
+                    project: graph_node.project.clone(),
+                    resource_type: String::new(),
+                    schema: String::new(),
+                    materialization: None,
+                    tags: vec![],
+                    depends_on,
+                    redacted: true,
+                });
+            }
+            Some(ModelMetadata {
+                name: node.get("name")?.as_str()?.to_string(),
+                unique_id: id.clone(),
+                project: graph_node.project.clone(),
+                resource_type: node.get("resource_type").and_then(|r| r.as_str()).unwrap_or_default().to_string(),
+                schema: node.get("schema").and_then(|s| s.as_str()).unwrap_or_default().to_string(),
+                materialization: node
+                    .get("config")
+                    .and_then(|c| c.get("materialized"))
+                    .and_then(|m| m.as_str())
+                    .map(String::from),
+                tags: node
+                    .get("tags")
+                    .and_then(|t| t.as_array())
+                    .map_or(vec![], |tags| tags.iter().filter_map(|t| t.as_str().map(String::from)).collect()),
+                depends_on,
+                redacted: false,
+            })
+        })
+        .collect();
+
+    Lineage { models: lineage_models }
 }
 
 
@@ -0,0 +1,393 @@
+use axum::{extract::Query, http::StatusCode, Json};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::config::{self, ProjectConfig};
+use crate::governance;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+use crate::utils::read_file;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, PartialOrd, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    #[default]
+    Error,
+}
+
+/// Rules file: `rules:` is a list of checks, each optionally scoped to
+/// `paths` (prefixes of `original_file_path`) and `resource_types`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    rules: Vec<LintRule>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LintRule {
+    /// Reported with each violation; defaults to the check name.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Only nodes whose `original_file_path` starts with one of these.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Only these resource types; defaults to `model`.
+    #[serde(default)]
+    pub resource_types: Vec<String>,
+    /// Keys other than the ones above must belong to the check, which
+    /// rejects the rest so a misspelled `path:` isn't silently ignored.
+    #[serde(flatten)]
+    pub check: Check,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "rule", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Check {
+    /// The node name matches a regex.
+    Naming {
+        #[serde(deserialize_with = "pattern")]
+        pattern: Regex,
+    },
+    /// Every key is set in meta; dots reach into nested meta objects.
+    RequiredMeta { keys: Vec<String> },
+    /// The node (and with `columns`, every column) has a description.
+    Description {
+        #[serde(default)]
+        columns: bool,
+    },
+    /// `materialized` is one of `allowed`.
+    Materialization { allowed: Vec<String> },
+    /// Public models enforce their contract.
+    PublicContract {},
+    /// At most `max_children` direct downstream nodes, tests excluded.
+    FanOut { max_children: usize },
+    /// The primary key has a `unique` test (or `unique_combination_of_columns`
+    /// for composite keys).
+    PrimaryKeyTest {},
+}
+
+/// Naming patterns are compiled once, when the rules are loaded.
+fn pattern<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(|e| serde::de::Error::custom(format!("invalid pattern: {}", e)))
+}
+
+impl Check {
+    fn kind(&self) -> &'static str {
+        match self {
+            Check::Naming { .. } => "naming",
+            Check::RequiredMeta { .. } => "required_meta",
+            Check::Description { .. } => "description",
+            Check::Materialization { .. } => "materialization",
+            Check::PublicContract {} => "public_contract",
+            Check::FanOut { .. } => "fan_out",
+            Check::PrimaryKeyTest {} => "primary_key_test",
+        }
+    }
+}
+
+impl LintRule {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(self.check.kind())
+    }
+
+    fn applies_to(&self, node: &Value) -> bool {
+        let resource_type = node.get("resource_type").and_then(|r| r.as_str()).unwrap_or_default();
+        let path = node.get("original_file_path").and_then(|p| p.as_str()).unwrap_or_default();
+        let type_matches = if self.resource_types.is_empty() {
+            resource_type == "model"
+        } else {
+            self.resource_types.iter().any(|t| t == resource_type)
+        };
+        type_matches && (self.paths.is_empty() || self.paths.iter().any(|prefix| path.starts_with(prefix.as_str())))
+    }
+}
+
+/// One rule a node breaks.
+#[derive(Serialize, ToSchema)]
+pub struct LintViolation {
+    pub rule: String,
+    pub severity: Severity,
+    pub unique_id: String,
+    pub name: String,
+    pub message: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LintReport {
+    pub errors: usize,
+    pub warnings: usize,
+    pub violations: Vec<LintViolation>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LintParams {
+    /// Only violations of this rule name.
+    rule: Option<String>,
+    /// `error` only shows errors; `warning` (default) shows both.
+    severity: Option<Severity>,
+}
+
+/// Used when `lint.rules_path` is unset.
+fn default_rules() -> Vec<LintRule> {
+    let rule = |severity, check| LintRule { name: None, severity, paths: vec![], resource_types: vec![], check };
+    vec![
+        rule(Severity::Warning, Check::Description { columns: false }),
+        rule(Severity::Error, Check::PublicContract {}),
+    ]
+}
+
+/// Parse and check a rules file.
+pub fn parse_rules(path: &Path) -> Result<Vec<LintRule>, String> {
+    let content = read_file(&path.display().to_string())
+        .map_err(|e| format!("Failed to read lint rules {}: {}", path.display(), e))?;
+    let file: RulesFile = serde_yaml::from_str(&content)
+        .map_err(|e| format!("Invalid lint rules {}: {}", path.display(), e))?;
+    Ok(file.rules)
+}
+
+/// The configured rules, or the built-in defaults.
+pub fn load_rules() -> Result<Vec<LintRule>, String> {
+    match &config::get().lint.rules_path {
+        Some(path) => parse_rules(path),
+        None => Ok(default_rules()),
+    }
+}
+
+fn text<'a>(node: &'a Value, key: &str) -> Option<&'a str> {
+    node.get(key).and_then(|v| v.as_str())
+}
+
+fn described(node: &Value) -> bool {
+    text(node, "description").is_some_and(|d| !d.trim().is_empty())
+}
+
+/// Names of the generic tests on `unique_id`, with the column they test.
+fn tests_on<'a>(manifest: &'a Value, unique_id: &str) -> Vec<(&'a str, Option<&'a str>)> {
+    let Some(nodes) = manifest.get("nodes").and_then(|n| n.as_object()) else {
+        return vec![];
+    };
+    nodes
+        .values()
+        .filter(|n| text(n, "resource_type") == Some("test"))
+        .filter(|n| {
+            text(n, "attached_node") == Some(unique_id)
+                || n.get("depends_on")
+                    .and_then(|d| d.get("nodes"))
+                    .and_then(|d| d.as_array())
+                    .is_some_and(|deps| deps.iter().any(|d| d.as_str() == Some(unique_id)))
+        })
+        .filter_map(|n| {
+            let name = n.get("test_metadata").and_then(|m| text(m, "name"))?;
+            Some((name, text(n, "column_name")))
+        })
+        .collect()
+}
+
+/// Messages for each way `node` breaks `check`.
+fn check_node(check: &Check, graph: &CatalogGraph, manifest: &Value, unique_id: &str, node: &Value) -> Vec<String> {
+    let name = text(node, "name").unwrap_or_default();
+    match check {
+        Check::Naming { pattern } => {
+            if pattern.is_match(name) { vec![] } else { vec![format!("name '{}' does not match {}", name, pattern)] }
+        }
+        Check::RequiredMeta { keys } => {
+            let meta = governance::node_meta(node);
+            keys.iter()
+                .filter(|key| governance::lookup(&meta, std::slice::from_ref(*key)).is_none())
+                .map(|key| format!("meta key '{}' is missing", key))
+                .collect()
+        }
+        Check::Description { columns } => {
+            let mut messages = vec![];
+            if !described(node) {
+                messages.push("missing description".to_string());
+            }
+            if *columns {
+                for (column, col) in node.get("columns").and_then(|c| c.as_object()).into_iter().flatten() {
+                    if !described(col) {
+                        messages.push(format!("column '{}' is missing a description", column));
+                    }
+                }
+            }
+            messages
+        }
+        Check::Materialization { allowed } => {
+            let materialized = node.get("config").and_then(|c| text(c, "materialized")).unwrap_or_default();
+            if allowed.iter().any(|a| a == materialized) {
+                vec![]
+            } else {
+                vec![format!("materialized as '{}', expected one of {}", materialized, allowed.join(", "))]
+            }
+        }
+        Check::PublicContract {} => {
+            let enforced = node
+                .get("config")
+                .and_then(|c| c.get("contract"))
+                .and_then(|c| c.get("enforced"))
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false);
+            if text(node, "access") == Some("public") && !enforced {
+                vec!["public model does not enforce its contract".to_string()]
+            } else {
+                vec![]
+            }
+        }
+        Check::FanOut { max_children } => {
+            let children = graph.children(unique_id).len();
+            if children > *max_children {
+                vec![format!("{} direct children, more than {}", children, max_children)]
+            } else {
+                vec![]
+            }
+        }
+        Check::PrimaryKeyTest {} => {
+            let primary_key: Vec<&str> = node
+                .get("primary_key")
+                .and_then(|p| p.as_array())
+                .map_or(vec![], |keys| keys.iter().filter_map(|k| k.as_str()).collect());
+            let tests = tests_on(manifest, unique_id);
+            match primary_key.as_slice() {
+                [] => vec!["no primary key declared".to_string()],
+                [column] => {
+                    let tested = tests.iter().any(|(test, col)| {
+                        *test == "unique" && col.is_some_and(|col| col.eq_ignore_ascii_case(column))
+                    });
+                    if tested { vec![] } else { vec![format!("primary key '{}' has no unique test", column)] }
+                }
+                columns => {
+                    let tested = tests.iter().any(|(test, _)| test.ends_with("unique_combination_of_columns"));
+                    if tested {
+                        vec![]
+                    } else {
+                        vec![format!("primary key ({}) has no unique_combination_of_columns test", columns.join(", "))]
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Run `rules` over a project's manifest, violations ordered by unique_id.
+pub fn lint(project: &ProjectConfig, access: &Access, rules: &[LintRule]) -> Result<LintReport, String> {
+    let manifest = load_manifest(project)?;
+    // Fan-out counts children in every project
+    let graph = CatalogGraph::build(&load_all_manifests());
+
+    let mut nodes: Vec<(&String, &Value)> = ["nodes", "sources", "exposures", "metrics"]
+        .iter()
+        .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+        .flat_map(|section| section.iter())
+        .filter(|(_, node)| access.can_see(node))
+        .collect();
+    nodes.sort_by_key(|(id, _)| *id);
+
+    let mut violations = vec![];
+    for (unique_id, node) in nodes {
+        for rule in rules.iter().filter(|rule| rule.applies_to(node)) {
+            for message in check_node(&rule.check, &graph, &manifest, unique_id, node) {
+                violations.push(LintViolation {
+                    rule: rule.name().to_string(),
+                    severity: rule.severity,
+                    unique_id: unique_id.clone(),
+                    name: text(node, "name").unwrap_or_default().to_string(),
+                    message,
+                });
+            }
+        }
+    }
+
+    Ok(LintReport {
+        errors: violations.iter().filter(|v| v.severity == Severity::Error).count(),
+        warnings: violations.iter().filter(|v| v.severity == Severity::Warning).count(),
+        violations,
+    })
+}
+
+/// Lint the project against the configured rules.
+#[utoipa::path(
+    get,
+    path = "/lint",
+    tag = "lint",
+    params(LintParams),
+    responses(
+        (status = 200, body = LintReport),
+        (status = 500, description = "Invalid rules file", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_lint(
+    Project(project): Project,
+    access: Access,
+    Query(params): Query<LintParams>,
+) -> Result<Json<LintReport>, (StatusCode, String)> {
+    let rules = load_rules().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let mut report = lint(project, &access, &rules).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+
+    let min_severity = params.severity.unwrap_or(Severity::Warning);
+    report.violations.retain(|v| v.severity >= min_severity && params.rule.as_ref().is_none_or(|rule| &v.rule == rule));
+    report.errors = report.violations.iter().filter(|v| v.severity == Severity::Error).count();
+    report.warnings = report.violations.len() - report.errors;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(yaml: &str) -> Result<Vec<LintRule>, String> {
+        serde_yaml::from_str::<RulesFile>(yaml).map(|file| file.rules).map_err(|e| e.to_string())
+    }
+
+    fn node(resource_type: &str, name: &str, path: &str) -> Value {
+        json!({"resource_type": resource_type, "name": name, "original_file_path": path})
+    }
+
+    #[test]
+    fn rules_reject_unknown_keys() {
+        let err = rules("rules:\n  - rule: naming\n    path: [models/]\n    pattern: ^stg_\n").unwrap_err();
+        assert!(err.contains("unknown field `path`"), "{}", err);
+        let err = rules("rules:\n  - rule: public_contract\n    sevrity: warning\n").unwrap_err();
+        assert!(err.contains("unknown field `sevrity`"), "{}", err);
+        let err = rules("rules:\n  - rule: naming\n    pattern: \"(\"\n").unwrap_err();
+        assert!(err.contains("invalid pattern"), "{}", err);
+    }
+
+    #[test]
+    fn rules_apply_to_models_under_their_paths_by_default() {
+        let yaml = "rules:\n  - rule: public_contract\n  - rule: primary_key_test\n    paths: [models/staging/]\n";
+        let rules = rules(yaml).unwrap();
+        let staging = node("model", "stg_orders", "models/staging/stg_orders.sql");
+        let marts = node("model", "orders", "models/marts/orders.sql");
+        assert!(rules[0].applies_to(&staging) && rules[0].applies_to(&marts));
+        assert!(!rules[0].applies_to(&node("seed", "people", "seeds/people.csv")));
+        assert!(rules[1].applies_to(&staging) && !rules[1].applies_to(&marts));
+    }
+
+    #[test]
+    fn rules_apply_to_the_listed_resource_types() {
+        let rules = rules("rules:\n  - rule: description\n    resource_types: [seed, source]\n").unwrap();
+        assert!(rules[0].applies_to(&node("seed", "people", "seeds/people.csv")));
+        assert!(!rules[0].applies_to(&node("model", "orders", "models/orders.sql")));
+        assert_eq!(rules[0].name(), "description");
+    }
+
+    #[test]
+    fn naming_checks_the_node_name() {
+        let rules = rules("rules:\n  - rule: naming\n    name: staging_prefix\n    pattern: ^stg_\n").unwrap();
+        let graph = CatalogGraph::build(&[]);
+        let check = |name| check_node(&rules[0].check, &graph, &json!({}), "model.p.x", &node("model", name, "x.sql"));
+        assert!(check("stg_orders").is_empty());
+        assert_eq!(check("orders"), ["name 'orders' does not match ^stg_"]);
+        assert_eq!(rules[0].name(), "staging_prefix");
+    }
+}
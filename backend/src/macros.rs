@@ -0,0 +1,275 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::projects::{load_manifest, manifest_project_name, Project};
+use crate::rbac::Access;
+
+#[derive(Serialize, ToSchema)]
+pub struct MacroSummary {
+    pub unique_id: String,
+    pub name: String,
+    pub package_name: String,
+    pub description: Option<String>,
+    /// Nodes and macros calling the macro directly that the caller may see.
+    pub callers: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MacroArgument {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub arg_type: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MacroCaller {
+    pub unique_id: String,
+    pub name: String,
+    pub resource_type: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MacroDetails {
+    pub unique_id: String,
+    pub name: String,
+    pub package_name: String,
+    pub original_file_path: Option<String>,
+    pub description: Option<String>,
+    pub arguments: Vec<MacroArgument>,
+    /// `Redacted` for callers who may not read SQL.
+    pub macro_sql: String,
+    /// Macros this one calls.
+    pub depends_on: Vec<String>,
+    /// Models, tests, seeds and snapshots calling the macro directly.
+    pub called_by_nodes: Vec<MacroCaller>,
+    pub called_by_macros: Vec<MacroCaller>,
+    /// Models calling the macro directly or through other macros: what a
+    /// change to it can affect.
+    pub impacted_models: Vec<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MacroParams {
+    /// Only macros of this package, e.g. `dbt_utils`.
+    package: Option<String>,
+    /// Only macros called by at least one node or macro.
+    #[serde(default)]
+    used: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct MacroPath {
+    /// Macro name or unique_id.
+    id: String,
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(String::from)
+}
+
+fn macro_deps(node: &Value) -> impl Iterator<Item = &str> {
+    node.get("depends_on")
+        .and_then(|d| d.get("macros"))
+        .and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.as_str())
+}
+
+/// Reverse of `depends_on.macros`: macro unique_id to the ids of the nodes
+/// and macros calling it.
+struct CallIndex<'a> {
+    manifest: &'a Value,
+    callers: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> CallIndex<'a> {
+    fn build(manifest: &'a Value) -> Self {
+        let mut callers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for section in ["nodes", "macros"] {
+            for (unique_id, node) in manifest.get(section).and_then(|s| s.as_object()).into_iter().flatten() {
+                for called in macro_deps(node) {
+                    callers.entry(called).or_default().push(unique_id);
+                }
+            }
+        }
+        CallIndex { manifest, callers }
+    }
+
+    fn callers(&self, unique_id: &str) -> &[&'a str] {
+        self.callers.get(unique_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn node(&self, unique_id: &str) -> Option<&'a Value> {
+        let section = if unique_id.starts_with("macro.") { "macros" } else { "nodes" };
+        self.manifest.get(section).and_then(|s| s.get(unique_id))
+    }
+
+    /// Direct callers of `unique_id` the caller may see, with their nodes.
+    fn visible_callers<'b>(
+        &'b self,
+        unique_id: &str,
+        access: &'b Access,
+    ) -> impl Iterator<Item = (&'a str, &'a Value)> + 'b {
+        self.callers(unique_id)
+            .iter()
+            .filter_map(move |caller| Some((*caller, self.node(caller).filter(|n| access.can_see(n))?)))
+    }
+
+    /// Models reached by walking callers up from `unique_id`.
+    fn impacted_models(&self, unique_id: &str) -> BTreeSet<String> {
+        let mut seen = BTreeSet::from([unique_id]);
+        let mut queue = VecDeque::from([unique_id]);
+        let mut models = BTreeSet::new();
+        while let Some(id) = queue.pop_front() {
+            for caller in self.callers(id) {
+                if !seen.insert(caller) {
+                    continue;
+                }
+                if caller.starts_with("macro.") {
+                    queue.push_back(caller);
+                } else if self.node(caller).and_then(|n| n.get("resource_type")).and_then(|r| r.as_str()) == Some("model") {
+                    models.insert(caller.to_string());
+                }
+            }
+        }
+        models
+    }
+}
+
+/// Resolve a macro name or unique_id, preferring the project's own macros
+/// over those of installed packages.
+fn find_macro<'a>(manifest: &'a Value, id: &str) -> Option<(&'a String, &'a Value)> {
+    let macros = manifest.get("macros").and_then(|m| m.as_object())?;
+    if let Some(found) = macros.get_key_value(id) {
+        return Some(found);
+    }
+    let own_package = manifest_project_name(manifest);
+    let mut matches: Vec<(&String, &Value)> =
+        macros.iter().filter(|(_, m)| m.get("name").and_then(|n| n.as_str()) == Some(id)).collect();
+    matches.sort_by_key(|(unique_id, m)| (m.get("package_name").and_then(|p| p.as_str()) != own_package, unique_id.as_str()));
+    matches.into_iter().next()
+}
+
+/// Macros of the project and its packages, ordered by unique_id.
+#[utoipa::path(
+    get,
+    path = "/macros",
+    tag = "macros",
+    params(MacroParams),
+    responses(
+        (status = 200, body = Vec<MacroSummary>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_macros(
+    Project(project): Project,
+    access: Access,
+    Query(params): Query<MacroParams>,
+) -> Result<Json<Vec<MacroSummary>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let index = CallIndex::build(&manifest);
+
+    let mut macros: Vec<MacroSummary> = manifest
+        .get("macros")
+        .and_then(|m| m.as_object())
+        .into_iter()
+        .flatten()
+        .filter(|(_, node)| access.can_see(node))
+        .filter(|(_, node)| params.package.as_ref().is_none_or(|p| text(node, "package_name").as_ref() == Some(p)))
+        .map(|(unique_id, node)| MacroSummary {
+            unique_id: unique_id.clone(),
+            name: text(node, "name").unwrap_or_default(),
+            package_name: text(node, "package_name").unwrap_or_default(),
+            description: text(node, "description"),
+            callers: index.visible_callers(unique_id, &access).count(),
+        })
+        .filter(|summary| !params.used || summary.callers > 0)
+        .collect();
+    macros.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+    Ok(Json(macros))
+}
+
+/// A macro's source, arguments and callers, with the models a change to it
+/// can affect.
+#[utoipa::path(
+    get,
+    path = "/macros/{id}",
+    tag = "macros",
+    params(MacroPath),
+    responses(
+        (status = 200, body = MacroDetails),
+        (status = 404, description = "Unknown or hidden macro", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_macro(
+    Project(project): Project,
+    access: Access,
+    Path(MacroPath { id }): Path<MacroPath>,
+) -> Result<Json<MacroDetails>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, node) = find_macro(&manifest, &id)
+        .filter(|(_, node)| access.can_see(node))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Macro not found: {}", id)))?;
+    let index = CallIndex::build(&manifest);
+
+    let mut called_by_nodes = vec![];
+    let mut called_by_macros = vec![];
+    for (caller, caller_node) in index.visible_callers(unique_id, &access) {
+        let entry = MacroCaller {
+            unique_id: caller.to_string(),
+            name: text(caller_node, "name").unwrap_or_default(),
+            resource_type: text(caller_node, "resource_type").unwrap_or_default(),
+        };
+        if caller.starts_with("macro.") {
+            called_by_macros.push(entry);
+        } else {
+            called_by_nodes.push(entry);
+        }
+    }
+    called_by_nodes.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+    called_by_macros.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+    Ok(Json(MacroDetails {
+        unique_id: unique_id.clone(),
+        name: text(node, "name").unwrap_or_default(),
+        package_name: text(node, "package_name").unwrap_or_default(),
+        original_file_path: text(node, "original_file_path"),
+        description: text(node, "description"),
+        arguments: node
+            .get("arguments")
+            .and_then(|a| a.as_array())
+            .into_iter()
+            .flatten()
+            .map(|arg| MacroArgument {
+                name: text(arg, "name").unwrap_or_default(),
+                arg_type: text(arg, "type"),
+                description: text(arg, "description"),
+            })
+            .collect(),
+        macro_sql: if access.sees_raw_sql() {
+            text(node, "macro_sql").unwrap_or_default()
+        } else {
+            "Redacted".to_string()
+        },
+        depends_on: macro_deps(node).map(String::from).collect(),
+        called_by_nodes,
+        called_by_macros,
+        impacted_models: index
+            .impacted_models(unique_id)
+            .into_iter()
+            .filter(|id| index.node(id).is_some_and(|n| access.can_see(n)))
+            .collect(),
+    }))
+}
@@ -1,38 +1,121 @@
 mod routes;
+mod annotations;
+mod audit;
+mod auth;
+mod classification;
+mod cli;
+mod config;
+mod coverage;
 mod dbt;
-mod models;
+mod diff;
+mod docs;
+mod drift;
+mod export;
+mod glossary;
+mod governance;
+mod graph;
+mod graphql;
+mod health;
+mod history;
 mod lineage;
+mod lint;
+mod macros;
+mod mesh;
+mod openapi;
+mod preview;
+mod profiling;
+mod projects;
+mod rbac;
+mod refresh;
+mod resources;
+mod search;
+mod semantic;
+mod site;
+mod store;
+mod unit_tests;
 mod utils;
+mod warehouse;
 
-use axum::{Router, Server};
-use std::net::SocketAddr;
-use tower_http::cors::{CorsLayer, Any};
-use env_logger;
+use axum::{http::HeaderValue, middleware, Router, Server};
+use clap::Parser;
+use tower_http::cors::{AllowOrigin, CorsLayer, Any};
 
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
 
+    let mut cli = config::Cli::parse();
+    let command = cli.command.take().unwrap_or(cli::Command::Serve);
+
+    // Load config from file, env vars and CLI flags
+    if command.needs_config() {
+        let app_config = match config::AppConfig::load(&cli) {
+            Ok(app_config) => app_config,
+            Err(err) => {
+                eprintln!("Invalid configuration: {}", err);
+                std::process::exit(1);
+            }
+        };
+        config::init(app_config);
+    }
+
+    let result = match command {
+        cli::Command::Serve => serve().await,
+        command => cli::run(command),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn serve() -> Result<(), String> {
+    let app_config = config::get();
+
+    // Open the metadata store and apply pending migrations
+    store::init()?;
+    store::spawn_ingester();
+
+    auth::init()?;
+
+    refresh::spawn_scheduler();
+    profiling::spawn_scheduler();
 
     // Initialize routes
     let app = Router::new()
         .merge(routes::init_routes())
+        // Authenticate callers when [auth] is configured
+        .layer(middleware::from_fn(auth::authenticate))
+        // Audit mutations and data reads, including rejected ones
+        .layer(middleware::from_fn(audit::record))
         // Add CORS middleware
         .layer(
             CorsLayer::new()
-                .allow_origin(Any) // Allow any origin for development; restrict in production
+                .allow_origin(cors_origins(&app_config.server.cors_origins))
                 .allow_methods(Any) // Allow any HTTP method
                 .allow_headers(Any), // Allow any headers
         );
 
     // Define server address
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+    let addr = app_config.listen_addr();
     println!("Server running at http://{}", addr);
 
     // Run server
     Server::bind(&addr)
         .serve(app.into_make_service())
         .await
-        .unwrap();
-}
\ No newline at end of file
+        .map_err(|e| format!("Server error: {}", e))
+}
+
+/// `*` allows any origin (handy for development); otherwise only the listed ones.
+fn cors_origins(origins: &[String]) -> AllowOrigin {
+    if origins.iter().any(|origin| origin == "*") {
+        return Any.into();
+    }
+    AllowOrigin::list(
+        origins
+            .iter()
+            .map(|origin| origin.parse::<HeaderValue>().expect("cors origins were validated at startup")),
+    )
+}
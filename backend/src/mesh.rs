@@ -0,0 +1,232 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::dbt::ModelPath;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+
+#[derive(Serialize, ToSchema)]
+pub struct Group {
+    name: String,
+    unique_id: String,
+    description: Option<String>,
+    owner: Value,
+    models: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AccessWarning {
+    /// The node doing the referencing.
+    node: String,
+    /// The model it references in breach of that model's access level.
+    references: String,
+    access: String,
+    node_group: Option<String>,
+    referenced_group: Option<String>,
+    reason: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ModelVersion {
+    unique_id: String,
+    version: Value,
+    is_latest: bool,
+    access: Option<String>,
+    deprecation_date: Option<String>,
+    /// Downstream nodes that ref this (non-latest) version explicitly.
+    pinned_by: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ModelVersions {
+    name: String,
+    latest_version: Value,
+    versions: Vec<ModelVersion>,
+}
+
+fn str_field(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+/// dbt writes `group` and `access` both at the top level and under `config`.
+pub fn group_of(node: &Value) -> Option<String> {
+    str_field(node, "group").or_else(|| node.get("config").and_then(|c| str_field(c, "group")))
+}
+
+pub fn access_of(node: &Value) -> Option<String> {
+    str_field(node, "access").or_else(|| node.get("config").and_then(|c| str_field(c, "access")))
+}
+
+/// Groups defined in the project with their owners and member models.
+#[utoipa::path(
+    get,
+    path = "/groups",
+    tag = "mesh",
+    responses(
+        (status = 200, body = Vec<Group>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_groups(Project(project): Project, access: Access) -> Result<Json<Vec<Group>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+
+    let mut members: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    if let Some(nodes) = manifest.get("nodes").and_then(|n| n.as_object()) {
+        for (unique_id, node) in nodes {
+            if node.get("resource_type").and_then(|r| r.as_str()) != Some("model") || !access.can_see(node) {
+                continue;
+            }
+            if let Some(group) = group_of(node) {
+                members.entry(group).or_default().push(unique_id.clone());
+            }
+        }
+    }
+
+    let mut groups: Vec<Group> = manifest
+        .get("groups")
+        .and_then(|g| g.as_object())
+        .map(|groups| {
+            groups
+                .iter()
+                .map(|(unique_id, group)| {
+                    let name = str_field(group, "name").unwrap_or_else(|| unique_id.clone());
+                    let mut models = members.remove(&name).unwrap_or_default();
+                    models.sort();
+                    Group {
+                        unique_id: unique_id.clone(),
+                        description: str_field(group, "description"),
+                        owner: group.get("owner").cloned().unwrap_or(Value::Null),
+                        models,
+                        name,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Json(groups))
+}
+
+/// References that break a model's access level: private models used outside
+/// their group, protected models used from another project. Only references
+/// between nodes the caller may see are reported.
+#[utoipa::path(get, path = "/access_warnings", tag = "mesh", responses((status = 200, body = Vec<AccessWarning>)))]
+pub async fn get_access_warnings(Project(project): Project, access: Access) -> Json<Vec<AccessWarning>> {
+    let graph = CatalogGraph::build(&load_all_manifests());
+
+    let mut warnings = vec![];
+    for (unique_id, graph_node) in &graph.nodes {
+        if graph_node.project != project.name || !access.can_see(&graph_node.node) {
+            continue;
+        }
+        let node = &graph_node.node;
+        let node_group = group_of(node);
+        for parent_id in graph.parents(unique_id) {
+            let Some(parent) = graph.nodes.get(parent_id).filter(|parent| access.can_see(&parent.node)) else {
+                continue;
+            };
+            let parent = &parent.node;
+            let level = access_of(parent);
+            let referenced_group = group_of(parent);
+
+            let reason = match level.as_deref() {
+                Some("private") if node_group.is_none() || node_group != referenced_group => format!(
+                    "private model is only accessible within group '{}'",
+                    referenced_group.as_deref().unwrap_or("<none>")
+                ),
+                Some("protected") if str_field(node, "package_name") != str_field(parent, "package_name") => {
+                    "protected model is only accessible within its own project".to_string()
+                }
+                _ => continue,
+            };
+
+            warnings.push(AccessWarning {
+                node: unique_id.clone(),
+                references: parent_id.clone(),
+                access: level.unwrap_or_default(),
+                node_group: node_group.clone(),
+                referenced_group,
+                reason,
+            });
+        }
+    }
+    warnings.sort_by(|a, b| (&a.node, &a.references).cmp(&(&b.node, &b.references)));
+
+    Json(warnings)
+}
+
+/// All versions of a model, which one is latest and who pins older ones.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/versions",
+    tag = "mesh",
+    params(ModelPath),
+    responses(
+        (status = 200, body = ModelVersions),
+        (status = 404, description = "Unknown or hidden model", body = String),
+    )
+)]
+pub async fn get_model_versions(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+) -> Result<Json<ModelVersions>, (StatusCode, String)> {
+    let graph = CatalogGraph::build(&load_all_manifests());
+    let not_found = || (StatusCode::NOT_FOUND, format!("Model not found: {}", id));
+
+    let model_id = graph.find(&id, &project.name).ok_or_else(not_found)?;
+    let model = &graph.nodes.get(model_id).filter(|m| access.can_see(&m.node)).ok_or_else(not_found)?.node;
+    let name = str_field(model, "name").unwrap_or_default();
+    let package = str_field(model, "package_name");
+    let latest_version = model.get("latest_version").cloned().unwrap_or(Value::Null);
+
+    let mut versions: Vec<ModelVersion> = graph
+        .nodes
+        .iter()
+        .filter(|(_, graph_node)| {
+            let node = &graph_node.node;
+            node.get("resource_type").and_then(|r| r.as_str()) == Some("model")
+                && str_field(node, "name").as_deref() == Some(name.as_str())
+                && str_field(node, "package_name") == package
+                && access.can_see(node)
+        })
+        .map(|(unique_id, graph_node)| {
+            let node = &graph_node.node;
+            let version = node.get("version").cloned().unwrap_or(Value::Null);
+            // Unversioned models have no "latest"; treat them as current
+            let is_latest = latest_version.is_null() || same_version(&version, &latest_version);
+            let mut pinned_by: Vec<String> = if is_latest {
+                vec![]
+            } else {
+                let visible = |child: &&String| graph.nodes.get(*child).is_some_and(|c| access.can_see(&c.node));
+                graph.children(unique_id).iter().filter(visible).cloned().collect()
+            };
+            pinned_by.sort();
+            ModelVersion {
+                unique_id: unique_id.clone(),
+                version,
+                is_latest,
+                access: access_of(node),
+                deprecation_date: str_field(node, "deprecation_date"),
+                pinned_by,
+            }
+        })
+        .collect();
+    versions.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+    Ok(Json(ModelVersions { name, latest_version, versions }))
+}
+
+/// Versions can be declared as numbers or strings (`v: 2` vs `v: "2"`).
+fn same_version(a: &Value, b: &Value) -> bool {
+    let as_text = |v: &Value| match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    as_text(a) == as_text(b)
+}
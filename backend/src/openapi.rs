@@ -0,0 +1,178 @@
+use utoipa::openapi::path::{ParameterBuilder, ParameterIn};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{ObjectBuilder, OpenApi as OpenApiDoc, Required, SchemaType};
+use utoipa::OpenApi;
+
+use crate::config;
+use crate::{
+    annotations, audit, classification, dbt, docs, drift, glossary, governance, health, history, lineage, lint, macros,
+    mesh, preview, profiling, projects, resources, search, semantic, unit_tests,
+};
+
+/// Routes served per project, un-prefixed for the default project.
+#[derive(OpenApi)]
+#[openapi(paths(
+    dbt::get_models,
+    dbt::get_model_details,
+    dbt::get_model_docs,
+    lineage::get_lineage,
+    dbt::get_manifest,
+    mesh::get_groups,
+    mesh::get_access_warnings,
+    mesh::get_model_versions,
+    governance::get_owner_resources,
+    classification::get_classification,
+    preview::get_model_preview,
+    profiling::get_model_profile,
+    profiling::run_profiling,
+    drift::get_drift,
+    history::list_snapshots,
+    history::get_model_runs,
+    unit_tests::get_unit_tests,
+    history::get_freshness,
+    annotations::get_model_annotation,
+    annotations::put_model_annotation,
+    annotations::get_column_annotation,
+    annotations::put_column_annotation,
+    search::search,
+    lint::get_lint,
+    health::get_health,
+    semantic::get_semantic_models,
+    semantic::get_semantic_model,
+    semantic::get_metrics,
+    semantic::get_metric,
+    semantic::get_saved_queries,
+    macros::get_macros,
+    macros::get_macro,
+    resources::get_seeds,
+    resources::get_seed,
+    preview::get_seed_preview,
+    resources::get_dbt_snapshots,
+    resources::get_dbt_snapshot,
+    docs::get_docs,
+    docs::get_doc,
+    docs::get_overview,
+))]
+struct ProjectApi;
+
+/// Global routes and every schema.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Data Catalog API", description = "Browse dbt projects: models, lineage, governance, data and the glossary."),
+    paths(
+        projects::list_projects,
+        audit::get_audit,
+        glossary::list_terms,
+        glossary::create_term,
+        glossary::get_term,
+        glossary::update_term,
+        glossary::delete_term,
+        glossary::add_link,
+        glossary::delete_link,
+        glossary::get_suggestions,
+    ),
+    components(schemas(
+        annotations::Annotation,
+        annotations::AnnotationUpdate,
+        audit::AuditEvent,
+        classification::ClassifiedColumn,
+        classification::ColumnClassification,
+        classification::Reason,
+        dbt::ModelSummary,
+        dbt::ModelDocs,
+        dbt::ModelGeneral,
+        dbt::ModelColumn,
+        dbt::DocumentedColumn,
+        dbt::RedactedColumn,
+        dbt::ModelSql,
+        drift::DriftEvent,
+        glossary::GlossaryTerm,
+        glossary::TermLink,
+        glossary::TermInput,
+        glossary::LinkInput,
+        glossary::LinkSuggestion,
+        governance::Governance,
+        governance::OwnedResource,
+        governance::OwnerResources,
+        history::SnapshotSummary,
+        health::HealthAggregate,
+        health::HealthComponents,
+        health::HealthReport,
+        health::HealthScore,
+        health::ModelHealth,
+        docs::DocBlock,
+        docs::DocBlockSummary,
+        history::RunResult,
+        history::SourceFreshness,
+        lineage::Lineage,
+        lineage::ModelMetadata,
+        lineage::Dependencies,
+        lint::LintReport,
+        lint::LintViolation,
+        lint::Severity,
+        macros::MacroArgument,
+        macros::MacroCaller,
+        macros::MacroDetails,
+        macros::MacroSummary,
+        mesh::Group,
+        mesh::AccessWarning,
+        mesh::ModelVersion,
+        mesh::ModelVersions,
+        preview::Preview,
+        profiling::RelationProfile,
+        profiling::ProfileRunStarted,
+        projects::ProjectSummary,
+        resources::DbtSnapshot,
+        resources::ResourceColumn,
+        resources::Seed,
+        search::SearchHit,
+        unit_tests::Fixture,
+        unit_tests::FixtureTable,
+        unit_tests::UnitTest,
+        unit_tests::UnitTestInput,
+        unit_tests::UnitTestOverride,
+        semantic::Dimension,
+        semantic::Entity,
+        semantic::Measure,
+        semantic::Metric,
+        semantic::SavedQuery,
+        semantic::SemanticModel,
+    ))
+)]
+struct ApiDoc;
+
+/// The OpenAPI document served at `/openapi.json`. Project routes are listed
+/// both un-prefixed (default project) and under `/projects/{project}`.
+pub fn document() -> OpenApiDoc {
+    let mut doc = ApiDoc::openapi();
+    let project_api = ProjectApi::openapi();
+
+    let project_param = ParameterBuilder::new()
+        .name("project")
+        .parameter_in(ParameterIn::Path)
+        .required(Required::True)
+        .description(Some("Configured project name"))
+        .schema(Some(ObjectBuilder::new().schema_type(SchemaType::String)))
+        .build();
+    for (path, item) in &project_api.paths.paths {
+        let mut item = item.clone();
+        item.parameters.get_or_insert_with(Vec::new).push(project_param.clone());
+        // Generated clients need distinct operation ids
+        for operation in item.operations.values_mut() {
+            operation.operation_id = operation.operation_id.take().map(|id| format!("{}_in_project", id));
+        }
+        doc.paths.paths.insert(format!("/projects/{{project}}{}", path), item);
+    }
+    doc.merge(project_api);
+
+    if config::get().auth.enabled() {
+        let components = doc.components.get_or_insert_with(Default::default);
+        components.add_security_scheme("token", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+        components.add_security_scheme("basic", SecurityScheme::Http(Http::new(HttpAuthScheme::Basic)));
+        doc.security = Some(vec![
+            SecurityRequirement::new("token", Vec::<String>::new()),
+            SecurityRequirement::new("basic", Vec::<String>::new()),
+        ]);
+    }
+    doc
+}
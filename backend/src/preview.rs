@@ -0,0 +1,226 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs;
+
+use crate::config;
+use crate::dbt::{find_model, ModelPath};
+use crate::projects::{load_manifest, manifest_project_name, Project};
+use crate::rbac::Access;
+use crate::resources::{find_resource, ResourcePath};
+use crate::utils::CsvRecords;
+use crate::warehouse::{self, WarehouseError};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PreviewParams {
+    /// Rows to return, capped at `preview.max_rows`.
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Preview {
+    model: String,
+    relation_name: String,
+    limit: usize,
+    columns: Value,
+    rows: Value,
+    row_count: usize,
+    /// True when more rows (or bytes) were available than returned.
+    truncated: bool,
+}
+
+/// Whether the preview config lets us read from `schema`.
+pub fn schema_allowed(schema: &str) -> bool {
+    let settings = &config::get().preview;
+    let listed = |schemas: &[String]| schemas.iter().any(|s| s.eq_ignore_ascii_case(schema));
+    !listed(&settings.denied_schemas) && (settings.allowed_schemas.is_empty() || listed(&settings.allowed_schemas))
+}
+
+pub fn warehouse_status(err: &WarehouseError) -> StatusCode {
+    match err {
+        WarehouseError::Unresolved(_) => StatusCode::SERVICE_UNAVAILABLE,
+        WarehouseError::TimedOut(_) => StatusCode::GATEWAY_TIMEOUT,
+        WarehouseError::Failed(_) => StatusCode::BAD_GATEWAY,
+    }
+}
+
+/// Blank out the values of sensitive columns the caller may not read.
+fn redact_columns(columns: &mut Value, rows: &mut Value, hidden: &HashSet<String>) {
+    let Some(columns) = columns.as_array_mut() else {
+        return;
+    };
+    let mut redacted = vec![];
+    for (i, column) in columns.iter_mut().enumerate() {
+        let name = column.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+        if hidden.contains(&name.to_lowercase()) {
+            column["redacted"] = Value::Bool(true);
+            redacted.push(i);
+        }
+    }
+    for row in rows.as_array_mut().into_iter().flatten().filter_map(|row| row.as_array_mut()) {
+        for &i in &redacted {
+            if let Some(value) = row.get_mut(i) {
+                *value = Value::Null;
+            }
+        }
+    }
+}
+
+/// First rows of a model's relation, read from the DuckDB target in read-only mode.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/preview",
+    tag = "data",
+    params(ModelPath, PreviewParams),
+    responses(
+        (status = 200, body = Preview),
+        (status = 403, description = "Previews are disabled for the model's schema", body = String),
+        (status = 404, description = "Unknown or hidden model", body = String),
+        (status = 422, description = "The model has no relation", body = String),
+        (status = 502, description = "The warehouse query failed", body = String),
+        (status = 503, description = "Manifest or warehouse unavailable", body = String),
+        (status = 504, description = "The warehouse query timed out", body = String),
+    )
+)]
+pub async fn get_model_preview(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+    Query(params): Query<PreviewParams>,
+) -> Result<Json<Preview>, (StatusCode, String)> {
+    let settings = &config::get().preview;
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let model = find_model(&manifest, &id)
+        .filter(|model| access.can_see(model))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Model not found: {}", id)))?;
+
+    let schema = model.get("schema").and_then(|s| s.as_str()).unwrap_or_default();
+    if !schema_allowed(schema) {
+        return Err((StatusCode::FORBIDDEN, format!("Previews are disabled for schema '{}'", schema)));
+    }
+    let relation_name = model
+        .get("relation_name")
+        .and_then(|r| r.as_str())
+        .ok_or_else(|| (StatusCode::UNPROCESSABLE_ENTITY, format!("{} has no relation to preview (ephemeral?)", id)))?;
+
+    let limit = params.limit.unwrap_or(settings.default_rows).clamp(1, settings.max_rows);
+    let database = warehouse::duckdb_path(project).map_err(|e| (warehouse_status(&e), e.to_string()))?;
+
+    let args = vec![
+        "--database".to_string(),
+        database.display().to_string(),
+        "--relation".to_string(),
+        relation_name.to_string(),
+        "--limit".to_string(),
+        limit.to_string(),
+        "--max-bytes".to_string(),
+        settings.max_bytes.to_string(),
+    ];
+    let result = warehouse::run_script(&settings.script, &args, settings.timeout_secs)
+        .await
+        .map_err(|e| (warehouse_status(&e), e.to_string()))?;
+
+    let mut columns = result.get("columns").cloned().unwrap_or(Value::Array(vec![]));
+    let mut rows = result.get("rows").cloned().unwrap_or(Value::Array(vec![]));
+    let unique_id = model.get("unique_id").and_then(|u| u.as_str()).unwrap_or_default();
+    if let Some(hidden) = access.hidden_columns().get(unique_id) {
+        redact_columns(&mut columns, &mut rows, hidden);
+    }
+
+    Ok(Json(Preview {
+        model: model.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+        relation_name: relation_name.to_string(),
+        limit,
+        columns,
+        row_count: rows.as_array().map_or(0, |r| r.len()),
+        rows,
+        truncated: result.get("truncated").and_then(|t| t.as_bool()).unwrap_or(false),
+    }))
+}
+
+/// First rows of a seed, read from its CSV file in the dbt project directory
+/// so it can be previewed before `dbt seed` loads it.
+#[utoipa::path(
+    get,
+    path = "/seeds/{id}/preview",
+    tag = "data",
+    params(ResourcePath, PreviewParams),
+    responses(
+        (status = 200, body = Preview),
+        (status = 403, description = "Previews are disabled for the seed's schema", body = String),
+        (status = 404, description = "Unknown or hidden seed", body = String),
+        (status = 422, description = "The seed belongs to an installed package", body = String),
+        (status = 503, description = "Manifest or seed file unavailable", body = String),
+    )
+)]
+pub async fn get_seed_preview(
+    Project(project): Project,
+    access: Access,
+    Path(ResourcePath { id }): Path<ResourcePath>,
+    Query(params): Query<PreviewParams>,
+) -> Result<Json<Preview>, (StatusCode, String)> {
+    let settings = &config::get().preview;
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, seed) = find_resource(&manifest, &access, "seed", &id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Seed not found: {}", id)))?;
+    let text = |node: &Value, key: &str| node.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    let schema = text(seed, "schema");
+    if !schema_allowed(&schema) {
+        return Err((StatusCode::FORBIDDEN, format!("Previews are disabled for schema '{}'", schema)));
+    }
+    // Package seeds live under dbt_packages/, outside the paths the manifest records
+    let package = text(seed, "package_name");
+    if manifest_project_name(&manifest).is_some_and(|own| own != package) {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("{} belongs to package '{}'", id, package)));
+    }
+
+    let path = project.project_dir.join(text(seed, "original_file_path"));
+    let content = fs::read_to_string(&path)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, format!("Failed to read seed file {}: {}", path.display(), e)))?;
+    let config = seed.get("config").cloned().unwrap_or_default();
+    let delimiter = config.get("delimiter").and_then(|d| d.as_str()).and_then(|d| d.chars().next()).unwrap_or(',');
+    let mut records = CsvRecords::new(&content, delimiter);
+
+    let header = records.next().unwrap_or_default();
+    let mut columns = Value::Array(
+        header
+            .iter()
+            .map(|name| json!({ "name": name, "type": config.get("column_types").and_then(|t| t.get(name)) }))
+            .collect(),
+    );
+
+    let limit = params.limit.unwrap_or(settings.default_rows).clamp(1, settings.max_rows);
+    let mut rows = vec![];
+    let mut bytes = 0;
+    let mut truncated = false;
+    for record in records.by_ref() {
+        bytes += record.iter().map(String::len).sum::<usize>();
+        if rows.len() == limit || bytes > settings.max_bytes {
+            truncated = true;
+            break;
+        }
+        rows.push(Value::Array(record.into_iter().map(Value::String).collect()));
+    }
+    let mut rows = Value::Array(rows);
+    if let Some(hidden) = access.hidden_columns().get(unique_id) {
+        redact_columns(&mut columns, &mut rows, hidden);
+    }
+
+    Ok(Json(Preview {
+        model: text(seed, "name"),
+        relation_name: text(seed, "relation_name"),
+        limit,
+        columns,
+        row_count: rows.as_array().map_or(0, |r| r.len()),
+        rows,
+        truncated,
+    }))
+}
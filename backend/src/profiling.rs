@@ -0,0 +1,251 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use rusqlite::params;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::{self, ProjectConfig};
+use crate::dbt::{find_model, ModelPath};
+use crate::preview::{schema_allowed, warehouse_status};
+use crate::projects::{load_manifest, Project};
+use crate::rbac::Access;
+use crate::store;
+use crate::warehouse;
+
+/// Column statistics of one relation as of `profiled_at`.
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct RelationProfile {
+    pub unique_id: String,
+    pub relation_name: String,
+    pub profiled_at: String,
+    pub row_count: Value,
+    /// Keyed by column name as reported by the warehouse.
+    pub columns: BTreeMap<String, Value>,
+}
+
+/// Projects with a profiling run in progress; runs of a project don't overlap.
+static RUNNING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Claim on a project's profiling run, released when dropped.
+struct RunningProfile(String);
+
+impl RunningProfile {
+    /// `None` while another run of the project is in progress.
+    fn claim(project: &ProjectConfig) -> Option<Self> {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        running.insert(project.name.clone()).then(|| RunningProfile(project.name.clone()))
+    }
+}
+
+impl Drop for RunningProfile {
+    fn drop(&mut self) {
+        RUNNING.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.0);
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ProfileRunStarted {
+    project: String,
+    status: String,
+}
+
+/// Latest profile of each relation of a project keyed by unique_id; empty
+/// until the first run. Older profiles stay in the store for trends.
+pub fn load_profiles(project: &ProjectConfig) -> HashMap<String, RelationProfile> {
+    store::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT unique_id, relation_name, profiled_at, row_count, columns FROM profiles
+             WHERE id IN (SELECT max(id) FROM profiles WHERE project = ?1 GROUP BY unique_id)",
+        )?;
+        let rows = stmt.query_map(params![project.name], |row| {
+            let row_count: String = row.get(3)?;
+            let columns: String = row.get(4)?;
+            Ok(RelationProfile {
+                unique_id: row.get(0)?,
+                relation_name: row.get(1)?,
+                profiled_at: row.get(2)?,
+                row_count: serde_json::from_str(&row_count).unwrap_or(Value::Null),
+                columns: serde_json::from_str(&columns).unwrap_or_default(),
+            })
+        })?;
+        rows.map(|row| row.map(|profile| (profile.unique_id.clone(), profile))).collect()
+    })
+    .unwrap_or_else(|e| {
+        error!("Failed to load profiles of project '{}': {}", project.name, e);
+        HashMap::new()
+    })
+}
+
+fn save_profile(project: &ProjectConfig, profile: &RelationProfile) -> Result<(), String> {
+    store::with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO profiles (project, unique_id, relation_name, profiled_at, row_count, columns)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                project.name,
+                profile.unique_id,
+                profile.relation_name,
+                profile.profiled_at,
+                profile.row_count.to_string(),
+                serde_json::to_string(&profile.columns).unwrap_or_default(),
+            ],
+        )
+    })
+    .map(|_| ())
+}
+
+/// Profile one relation with the profiling script.
+async fn profile_relation(database: &str, unique_id: &str, relation_name: &str) -> Result<RelationProfile, String> {
+    let settings = &config::get().profiling;
+    let args = vec![
+        "--database".to_string(),
+        database.to_string(),
+        "--relation".to_string(),
+        relation_name.to_string(),
+        "--top-k".to_string(),
+        settings.top_k.to_string(),
+    ];
+    let result = warehouse::run_script(&settings.script, &args, settings.timeout_secs)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let columns = result
+        .get("columns")
+        .and_then(|c| c.as_object())
+        .map(|cols| cols.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default();
+
+    Ok(RelationProfile {
+        unique_id: unique_id.to_string(),
+        relation_name: relation_name.to_string(),
+        profiled_at: chrono::Utc::now().to_rfc3339(),
+        row_count: result.get("row_count").cloned().unwrap_or(Value::Null),
+        columns,
+    })
+}
+
+/// Profile every materialized model of a project and store the results.
+/// Relations that fail keep their previous profile as the latest one.
+pub async fn profile_project(project: &ProjectConfig) -> Result<usize, String> {
+    let manifest = load_manifest(project)?;
+    let database = warehouse::duckdb_path(project).map_err(|e| e.to_string())?;
+    let database = database.display().to_string();
+
+    let mut profiled = 0;
+    let nodes = manifest.get("nodes").and_then(|n| n.as_object()).cloned().unwrap_or_default();
+    for (unique_id, node) in &nodes {
+        if node.get("resource_type").and_then(|r| r.as_str()) != Some("model") {
+            continue;
+        }
+        let schema = node.get("schema").and_then(|s| s.as_str()).unwrap_or_default();
+        let Some(relation_name) = node.get("relation_name").and_then(|r| r.as_str()) else {
+            continue; // ephemeral
+        };
+        if !schema_allowed(schema) {
+            continue;
+        }
+
+        match profile_relation(&database, unique_id, relation_name).await {
+            Ok(profile) => {
+                save_profile(project, &profile)?;
+                profiled += 1;
+            }
+            Err(err) => warn!("Failed to profile {}: {}", unique_id, err),
+        }
+    }
+
+    info!("Profiled {} relations for project '{}'", profiled, project.name);
+    Ok(profiled)
+}
+
+/// Profile every project periodically when `profiling.interval_secs` is non-zero.
+pub fn spawn_scheduler() {
+    let interval_secs = config::get().profiling.interval_secs;
+    if interval_secs == 0 {
+        return;
+    }
+
+    info!("Profiling columns every {} seconds", interval_secs);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            for project in &config::get().projects {
+                let Some(_running) = RunningProfile::claim(project) else {
+                    info!("Skipping scheduled profiling of project '{}': a run is in progress", project.name);
+                    continue;
+                };
+                if let Err(err) = profile_project(project).await {
+                    error!("Scheduled profiling failed for project '{}': {}", project.name, err);
+                }
+            }
+        }
+    });
+}
+
+/// Latest column profile of a model.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/profile",
+    tag = "data",
+    params(ModelPath),
+    responses(
+        (status = 200, body = RelationProfile),
+        (status = 404, description = "Unknown or hidden model, or not profiled yet", body = String),
+    )
+)]
+pub async fn get_model_profile(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+) -> Result<Json<RelationProfile>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let unique_id = find_model(&manifest, &id)
+        .filter(|model| access.can_see(model))
+        .and_then(|model| model.get("unique_id"))
+        .and_then(|u| u.as_str())
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Model not found: {}", id)))?;
+
+    let mut profile = load_profiles(project)
+        .remove(unique_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("{} has not been profiled yet", id)))?;
+    if let Some(hidden) = access.hidden_columns().get(unique_id) {
+        profile.columns.retain(|name, _| !hidden.contains(&name.to_lowercase()));
+    }
+    Ok(Json(profile))
+}
+
+/// Start a profiling run for the project in the background.
+#[utoipa::path(
+    post,
+    path = "/profiles/run",
+    tag = "data",
+    responses(
+        (status = 202, body = ProfileRunStarted),
+        (status = 409, description = "A profiling run of the project is in progress", body = String),
+        (status = 503, description = "Warehouse unavailable", body = String),
+    )
+)]
+pub async fn run_profiling(Project(project): Project) -> Result<(StatusCode, Json<ProfileRunStarted>), (StatusCode, String)> {
+    // Fail fast on an unresolvable warehouse instead of only logging it later
+    warehouse::duckdb_path(project).map_err(|e| (warehouse_status(&e), e.to_string()))?;
+    let running = RunningProfile::claim(project).ok_or_else(|| {
+        (StatusCode::CONFLICT, format!("Profiling of project '{}' is already running", project.name))
+    })?;
+
+    tokio::spawn(async move {
+        let _running = running;
+        if let Err(err) = profile_project(project).await {
+            error!("Profiling failed for project '{}': {}", project.name, err);
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ProfileRunStarted { project: project.name.clone(), status: "started".to_string() }),
+    ))
+}
@@ -0,0 +1,107 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use log::error;
+use serde::Serialize;
+use utoipa::ToSchema;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::config::{self, ProjectConfig};
+
+/// The dbt project a request targets: the `:project` segment of
+/// `/projects/:project/...`, or the default project for un-prefixed routes.
+pub struct Project(pub &'static ProjectConfig);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Project {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Routes without path params reject `Path`, which just means "no project segment"
+        let params = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map(|Path(params)| params)
+            .unwrap_or_default();
+
+        match params.get("project") {
+            Some(name) => config::get()
+                .project(name)
+                .map(Project)
+                .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Unknown project: {}", name))),
+            None => Ok(Project(config::get().default_project())),
+        }
+    }
+}
+
+/// Read and parse a project's enriched manifest.
+pub fn load_manifest(project: &ProjectConfig) -> Result<Value, String> {
+    let data = fs::read_to_string(&project.manifest_path).map_err(|e| {
+        format!("Failed to read {} for project '{}': {}", project.manifest_path.display(), project.name, e)
+    })?;
+    serde_json::from_str(&data).map_err(|e| {
+        format!("Failed to parse {} for project '{}': {}", project.manifest_path.display(), project.name, e)
+    })
+}
+
+/// Load every configured project's manifest, skipping (and logging) the ones
+/// that are not available yet so one broken project doesn't hide the others.
+pub fn load_all_manifests() -> Vec<(&'static ProjectConfig, Value)> {
+    config::get()
+        .projects
+        .iter()
+        .filter_map(|project| match load_manifest(project) {
+            Ok(manifest) => Some((project, manifest)),
+            Err(err) => {
+                error!("{}", err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The dbt `name:` of the project that produced a manifest.
+pub fn manifest_project_name(manifest: &Value) -> Option<&str> {
+    manifest
+        .get("metadata")
+        .and_then(|m| m.get("project_name"))
+        .and_then(|n| n.as_str())
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ProjectSummary {
+    name: String,
+    dbt_project_name: Option<String>,
+    project_dir: String,
+    is_default: bool,
+    available: bool,
+}
+
+/// Configured dbt projects.
+#[utoipa::path(get, path = "/projects", tag = "projects", responses((status = 200, body = Vec<ProjectSummary>)))]
+pub async fn list_projects() -> Json<Vec<ProjectSummary>> {
+    let config = config::get();
+    let projects = config
+        .projects
+        .iter()
+        .map(|project| {
+            let manifest = load_manifest(project).ok();
+            ProjectSummary {
+                name: project.name.clone(),
+                dbt_project_name: manifest
+                    .as_ref()
+                    .and_then(manifest_project_name)
+                    .map(String::from),
+                project_dir: project.project_dir.display().to_string(),
+                is_default: project.name == config.default_project().name,
+                available: manifest.is_some(),
+            }
+        })
+        .collect();
+
+    Json(projects)
+}
@@ -0,0 +1,330 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+
+use crate::auth::AuthUser;
+use crate::classification::{self, NodeClassifications};
+use crate::config::{self, RoleConfig};
+use crate::graph::CatalogGraph;
+use crate::mesh;
+use crate::projects::load_all_manifests;
+
+/// What the caller may see. Unrestricted when `[rbac]` has no roles.
+pub struct Access {
+    /// `None` when RBAC is off.
+    roles: Option<Vec<&'static RoleConfig>>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Access {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let rbac = &config::get().rbac;
+        if rbac.roles.is_empty() {
+            return Ok(Access { roles: None });
+        }
+
+        let names: Vec<&String> = match parts.extensions.get::<AuthUser>() {
+            Some(user) => {
+                let held: Vec<&String> = user.roles.iter().filter(|r| rbac.roles.iter().any(|c| &c.name == *r)).collect();
+                if held.is_empty() { rbac.default_roles.iter().collect() } else { held }
+            }
+            None => rbac.anonymous_roles.iter().collect(),
+        };
+        let roles = rbac.roles.iter().filter(|role| names.contains(&&role.name)).collect();
+        Ok(Access { roles: Some(roles) })
+    }
+}
+
+fn strings(value: Option<&Value>) -> impl Iterator<Item = &str> {
+    value.and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str())
+}
+
+fn grants(role: &RoleConfig, node: &Value) -> bool {
+    let package = node.get("package_name").and_then(|p| p.as_str());
+    let group = mesh::group_of(node);
+    // dbt defaults model access to protected; other resources have none
+    let access = mesh::access_of(node).or_else(|| {
+        (node.get("resource_type").and_then(|r| r.as_str()) == Some("model")).then(|| "protected".to_string())
+    });
+    let mut tags = strings(node.get("tags")).chain(strings(node.get("config").and_then(|c| c.get("tags"))));
+
+    role.packages.iter().any(|p| p == "*" || Some(p.as_str()) == package)
+        || tags.any(|tag| role.tags.iter().any(|t| t == tag))
+        || group.is_some_and(|group| role.groups.contains(&group))
+        || access.is_some_and(|access| role.access.contains(&access))
+}
+
+/// `sql` fixtures of unit tests are SQL the caller may not read either.
+fn redact_sql_fixture(fixture: &mut Value) {
+    let is_sql = fixture.get("format").and_then(|f| f.as_str()) == Some("sql");
+    if is_sql && fixture.get("rows").is_some_and(|rows| rows.is_string()) {
+        fixture["rows"] = json!("Redacted");
+    }
+}
+
+/// Manifest sections whose entries RBAC hides.
+const SECTIONS: [&str; 6] = ["nodes", "sources", "exposures", "metrics", "semantic_models", "saved_queries"];
+
+impl Access {
+    /// Sees everything, for the CLI which runs with the operator's own access.
+    pub fn unrestricted() -> Self {
+        Access { roles: None }
+    }
+
+    /// Sees what the named roles grant, for exports made on their behalf.
+    pub fn for_roles(names: &[String]) -> Result<Self, String> {
+        let rbac = &config::get().rbac;
+        let roles = names
+            .iter()
+            .map(|name| rbac.roles.iter().find(|role| &role.name == name).ok_or_else(|| format!("Unknown role: {}", name)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Access { roles: Some(roles) })
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.roles.is_some()
+    }
+
+    pub fn can_see(&self, node: &Value) -> bool {
+        match &self.roles {
+            None => true,
+            Some(roles) => roles.iter().any(|role| grants(role, node)),
+        }
+    }
+
+    /// `can_see` for an entry of the manifest by unique_id, e.g. one named in
+    /// stored history. Entries gone from the manifest are only visible to
+    /// unrestricted callers.
+    pub fn can_see_id(&self, manifest: &Value, unique_id: &str) -> bool {
+        !self.is_restricted()
+            || SECTIONS
+                .iter()
+                .find_map(|section| manifest.get(section).and_then(|s| s.get(unique_id)))
+                .is_some_and(|node| self.can_see(node))
+    }
+
+    pub fn sees_raw_sql(&self) -> bool {
+        self.roles.as_ref().is_none_or(|roles| roles.iter().any(|role| role.raw_sql))
+    }
+
+    pub fn sees_sensitive_columns(&self) -> bool {
+        self.roles.as_ref().is_none_or(|roles| roles.iter().any(|role| role.sensitive_columns))
+    }
+
+    /// Lowercased names of the columns the caller may not read, per node.
+    pub fn hidden_columns(&self) -> HashMap<String, HashSet<String>> {
+        if self.sees_sensitive_columns() {
+            return HashMap::new();
+        }
+        self.hidden_columns_of(&classification::classify(&CatalogGraph::build(&load_all_manifests())))
+    }
+
+    /// `hidden_columns` from classifications the request already computed.
+    pub fn hidden_columns_of(&self, classifications: &HashMap<String, NodeClassifications>) -> HashMap<String, HashSet<String>> {
+        if self.sees_sensitive_columns() {
+            return HashMap::new();
+        }
+        classifications
+            .iter()
+            .map(|(unique_id, columns)| (unique_id.clone(), columns.keys().cloned().collect()))
+            .collect()
+    }
+
+    /// Replace the SQL of a node (or macro) for callers who may not read it.
+    fn redact_sql(&self, node: &mut Value) {
+        if self.sees_raw_sql() {
+            return;
+        }
+        for field in ["raw_code", "compiled_code", "raw_sql", "compiled_sql", "macro_sql"] {
+            if node.get(field).is_some() {
+                node[field] = json!("Redacted");
+            }
+        }
+    }
+
+    /// A copy of the manifest without the nodes, SQL and sensitive columns
+    /// the caller may not see.
+    pub fn filter_manifest(&self, mut manifest: Value) -> Value {
+        if !self.is_restricted() {
+            return manifest;
+        }
+        let hidden_columns = self.hidden_columns();
+
+        let mut hidden = HashSet::new();
+        for section in SECTIONS {
+            let Some(entries) = manifest.get_mut(section).and_then(|s| s.as_object_mut()) else {
+                continue;
+            };
+            entries.retain(|unique_id, node| {
+                let visible = self.can_see(node);
+                if !visible {
+                    hidden.insert(unique_id.clone());
+                }
+                visible
+            });
+            for (unique_id, node) in entries.iter_mut() {
+                self.redact_sql(node);
+                if let (Some(columns), Some(hidden)) =
+                    (node.get_mut("columns").and_then(|c| c.as_object_mut()), hidden_columns.get(unique_id))
+                {
+                    columns.retain(|name, _| !hidden.contains(&name.to_lowercase()));
+                }
+            }
+        }
+
+        // Unit tests carry fixture rows of the model they test and its inputs
+        if let Some(tests) = manifest.get_mut("unit_tests").and_then(|u| u.as_object_mut()) {
+            tests.retain(|unique_id, test| {
+                let mut depends_on = strings(test.get("depends_on").and_then(|d| d.get("nodes")));
+                let depends_on_hidden = depends_on.any(|id| hidden.contains(id));
+                let visible = self.can_see(test) && !depends_on_hidden;
+                if !visible {
+                    hidden.insert(unique_id.clone());
+                }
+                visible
+            });
+            if !self.sees_raw_sql() {
+                for test in tests.values_mut() {
+                    if let Some(given) = test.get_mut("given").and_then(|g| g.as_array_mut()) {
+                        given.iter_mut().for_each(redact_sql_fixture);
+                    }
+                    if let Some(expect) = test.get_mut("expect") {
+                        redact_sql_fixture(expect);
+                    }
+                }
+            }
+        }
+
+        // Disabled nodes are listed per unique_id, one entry per definition
+        if let Some(disabled) = manifest.get_mut("disabled").and_then(|d| d.as_object_mut()) {
+            for nodes in disabled.values_mut().filter_map(|nodes| nodes.as_array_mut()) {
+                nodes.retain(|node| self.can_see(node));
+                for node in nodes.iter_mut() {
+                    self.redact_sql(node);
+                }
+            }
+            disabled.retain(|_, nodes| nodes.as_array().is_none_or(|nodes| !nodes.is_empty()));
+        }
+
+        if let Some(macros) = manifest.get_mut("macros").and_then(|m| m.as_object_mut()) {
+            for macro_node in macros.values_mut() {
+                self.redact_sql(macro_node);
+            }
+        }
+
+        // The dependency maps would still name the hidden nodes
+        for map in ["parent_map", "child_map"] {
+            if let Some(entries) = manifest.get_mut(map).and_then(|m| m.as_object_mut()) {
+                entries.retain(|unique_id, _| !hidden.contains(unique_id));
+                for ids in entries.values_mut().filter_map(|ids| ids.as_array_mut()) {
+                    ids.retain(|id| id.as_str().is_none_or(|id| !hidden.contains(id)));
+                }
+            }
+        }
+        manifest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(toml: &str) -> &'static RoleConfig {
+        Box::leak(Box::new(toml::from_str(&format!("name = \"analyst\"\n{}", toml)).unwrap()))
+    }
+
+    fn model(fields: Value) -> Value {
+        let mut node = json!({"resource_type": "model", "package_name": "shop", "tags": []});
+        node.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
+        node
+    }
+
+    #[test]
+    fn grants_matches_packages_tags_groups_and_access() {
+        assert!(grants(role(r#"packages = ["shop"]"#), &model(json!({}))));
+        assert!(grants(role(r#"packages = ["*"]"#), &model(json!({"package_name": "other"}))));
+        assert!(grants(role(r#"tags = ["finance"]"#), &model(json!({"config": {"tags": ["finance"]}}))));
+        assert!(grants(role(r#"groups = ["sales"]"#), &model(json!({"group": "sales"}))));
+        assert!(!grants(role(r#"packages = ["other"]"#), &model(json!({}))));
+        assert!(!grants(role(r#"tags = ["finance"]"#), &model(json!({"tags": ["marketing"]}))));
+    }
+
+    #[test]
+    fn grants_reads_group_and_access_from_config() {
+        assert!(grants(role(r#"groups = ["sales"]"#), &model(json!({"config": {"group": "sales"}}))));
+        assert!(grants(role(r#"access = ["public"]"#), &model(json!({"config": {"access": "public"}}))));
+        assert!(!grants(role(r#"access = ["public"]"#), &model(json!({"config": {"access": "private"}}))));
+    }
+
+    #[test]
+    fn grants_defaults_model_access_to_protected() {
+        let protected = role(r#"access = ["protected"]"#);
+        assert!(grants(protected, &model(json!({}))));
+        assert!(!grants(protected, &json!({"resource_type": "source", "package_name": "shop"})));
+    }
+
+    #[test]
+    fn can_see_needs_any_held_role_to_grant() {
+        let node = model(json!({"group": "sales"}));
+        assert!(Access::unrestricted().can_see(&node));
+        assert!(!Access { roles: Some(vec![]) }.can_see(&node));
+        assert!(!Access { roles: Some(vec![role(r#"groups = ["finance"]"#)]) }.can_see(&node));
+        let both = Access { roles: Some(vec![role(r#"groups = ["finance"]"#), role(r#"groups = ["sales"]"#)]) };
+        assert!(both.can_see(&node));
+    }
+
+    #[test]
+    fn can_see_id_hides_entries_missing_from_the_manifest() {
+        let source = json!({"resource_type": "source", "package_name": "shop"});
+        let manifest = json!({"sources": {"source.shop.raw.orders": source}});
+        let access = Access { roles: Some(vec![role(r#"packages = ["shop"]"#)]) };
+        assert!(access.can_see_id(&manifest, "source.shop.raw.orders"));
+        assert!(!access.can_see_id(&manifest, "source.shop.raw.gone"));
+        assert!(Access::unrestricted().can_see_id(&manifest, "source.shop.raw.gone"));
+    }
+
+    #[test]
+    fn filter_manifest_hides_unit_tests_disabled_nodes_and_macro_sql() {
+        let shop = r#"packages = ["shop"]
+raw_sql = false
+sensitive_columns = true"#;
+        let access = Access { roles: Some(vec![role(shop)]) };
+        let manifest = json!({
+            "nodes": {
+                "model.shop.orders": {"resource_type": "model", "package_name": "shop", "raw_code": "select 1"},
+                "model.hr.salaries": {"resource_type": "model", "package_name": "hr", "raw_code": "select 2"},
+            },
+            "unit_tests": {
+                "unit_test.shop.orders.t": {
+                    "package_name": "shop",
+                    "depends_on": {"nodes": ["model.shop.orders"]},
+                    "given": [{"input": "ref('x')", "format": "sql", "rows": "select 1 as id"}],
+                    "expect": {"format": "dict", "rows": [{"id": 1}]},
+                },
+                "unit_test.hr.salaries.t": {"package_name": "hr", "depends_on": {"nodes": ["model.hr.salaries"]}},
+                "unit_test.shop.salaries.t": {"package_name": "shop", "depends_on": {"nodes": ["model.hr.salaries"]}},
+            },
+            "disabled": {
+                "model.shop.old": [{"resource_type": "model", "package_name": "shop", "raw_code": "select 3"}],
+                "model.hr.old": [{"resource_type": "model", "package_name": "hr", "raw_code": "select 4"}],
+            },
+            "macros": {"macro.shop.money": {"package_name": "shop", "macro_sql": "{% macro money() %}{% endmacro %}"}},
+            "child_map": {"model.shop.orders": [], "model.hr.salaries": ["unit_test.shop.salaries.t"]},
+        });
+
+        let filtered = access.filter_manifest(manifest);
+        let keys = |section: &str| filtered[section].as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys("nodes"), ["model.shop.orders"]);
+        assert_eq!(keys("unit_tests"), ["unit_test.shop.orders.t"]);
+        assert_eq!(keys("disabled"), ["model.shop.old"]);
+        assert_eq!(keys("child_map"), ["model.shop.orders"]);
+        assert_eq!(filtered["nodes"]["model.shop.orders"]["raw_code"], "Redacted");
+        assert_eq!(filtered["unit_tests"]["unit_test.shop.orders.t"]["given"][0]["rows"], "Redacted");
+        assert_eq!(filtered["unit_tests"]["unit_test.shop.orders.t"]["expect"]["rows"], json!([{"id": 1}]));
+        assert_eq!(filtered["disabled"]["model.shop.old"][0]["raw_code"], "Redacted");
+        assert_eq!(filtered["macros"]["macro.shop.money"]["macro_sql"], "Redacted");
+    }
+}
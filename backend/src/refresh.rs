@@ -0,0 +1,78 @@
+use log::{error, info};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::config::{self, ProjectConfig};
+use crate::dbt::run_dbt_command;
+use crate::store;
+
+/// Refresh every configured project, continuing past failures.
+pub fn run_refresh() -> Result<(), String> {
+    let failures: Vec<String> = config::get()
+        .projects
+        .iter()
+        .filter_map(|project| refresh_project(project).err())
+        .collect();
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+/// Regenerate dbt docs and rebuild the enriched manifest, mirroring
+/// `cache/refresh_cache.sh` but driven by the loaded config.
+pub fn refresh_project(project: &ProjectConfig) -> Result<(), String> {
+    let config = config::get();
+
+    info!("Generating dbt documentation for project '{}'", project.name);
+    run_dbt_command(project, &["docs", "generate"])
+        .map_err(|e| format!("project '{}': {}", project.name, e))?;
+
+    info!("Enriching manifest with {}", config.artifacts.enrich_script.display());
+    let output = Command::new("python3")
+        .arg(&config.artifacts.enrich_script)
+        .env("CATALOG_TARGET_DIR", project.target_dir())
+        .env("CATALOG_MANIFEST_PATH", &project.manifest_path)
+        .env("CATALOG_SNAPSHOT_PATH", project.snapshot_path())
+        .env("CATALOG_DRIFT_PATH", project.drift_path())
+        .output()
+        .map_err(|e| format!("Failed to run enrichment script: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "project '{}': enrichment script failed with status: {}\nError: {}",
+            project.name,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    store::ingest_project(project).map_err(|e| format!("project '{}': {}", project.name, e))?;
+
+    info!("Cache refreshed successfully for project '{}'", project.name);
+    Ok(())
+}
+
+/// Periodically refresh the cache when `refresh.interval_secs` is non-zero.
+pub fn spawn_scheduler() {
+    let interval_secs = config::get().refresh.interval_secs;
+    if interval_secs == 0 {
+        return;
+    }
+
+    info!("Refreshing the cache every {} seconds", interval_secs);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            // dbt and the enrichment script block, keep them off the async workers
+            match tokio::task::spawn_blocking(run_refresh).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => error!("Scheduled cache refresh failed: {}", err),
+                Err(err) => error!("Scheduled cache refresh panicked: {}", err),
+            }
+        }
+    });
+}
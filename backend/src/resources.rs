@@ -0,0 +1,290 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::docs;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+
+/// A catalog column; sensitive columns the caller may not read are left out.
+#[derive(Serialize, ToSchema)]
+pub struct ResourceColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: Option<String>,
+    pub description: Option<String>,
+    /// Rendered to sanitized HTML, with `doc()` references resolved.
+    pub description_html: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Seed {
+    pub unique_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Rendered to sanitized HTML, with `doc()` references resolved.
+    pub description_html: Option<String>,
+    pub relation_name: Option<String>,
+    /// CSV file, relative to the dbt project directory.
+    pub original_file_path: Option<String>,
+    pub delimiter: String,
+    /// Column types overridden in the seed config.
+    pub column_types: BTreeMap<String, String>,
+    pub quote_columns: Option<bool>,
+    pub columns: Vec<ResourceColumn>,
+    /// Nodes built from the seed, possibly in other projects.
+    pub children: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DbtSnapshot {
+    pub unique_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Rendered to sanitized HTML, with `doc()` references resolved.
+    pub description_html: Option<String>,
+    pub relation_name: Option<String>,
+    pub original_file_path: Option<String>,
+    /// `timestamp` or `check`.
+    pub strategy: Option<String>,
+    /// A column name or a list of them.
+    pub unique_key: Option<Value>,
+    /// Timestamp column of the `timestamp` strategy.
+    pub updated_at: Option<String>,
+    /// `all` or the columns compared by the `check` strategy.
+    pub check_cols: Option<Value>,
+    /// `ignore`, `invalidate` or `new_record`.
+    pub hard_deletes: Option<String>,
+    pub columns: Vec<ResourceColumn>,
+    pub parents: Vec<String>,
+    pub children: Vec<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct ResourcePath {
+    /// Name or unique_id.
+    pub id: String,
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(String::from)
+}
+
+fn config_value(node: &Value, key: &str) -> Option<Value> {
+    node.get("config").and_then(|c| c.get(key)).filter(|v| !v.is_null()).cloned()
+}
+
+fn config_text(node: &Value, key: &str) -> Option<String> {
+    node.get("config").and_then(|c| text(c, key))
+}
+
+/// Visible nodes of `resource_type` in the manifest, ordered by unique_id.
+fn nodes_of<'a>(manifest: &'a Value, access: &Access, resource_type: &str) -> Vec<(&'a String, &'a Value)> {
+    let mut nodes: Vec<(&String, &Value)> = manifest
+        .get("nodes")
+        .and_then(|n| n.as_object())
+        .into_iter()
+        .flatten()
+        .filter(|(_, node)| node.get("resource_type").and_then(|r| r.as_str()) == Some(resource_type))
+        .filter(|(_, node)| access.can_see(node))
+        .collect();
+    nodes.sort_by_key(|(id, _)| *id);
+    nodes
+}
+
+/// A visible node of `resource_type` by name or unique_id.
+pub fn find_resource<'a>(
+    manifest: &'a Value,
+    access: &Access,
+    resource_type: &str,
+    id: &str,
+) -> Option<(&'a String, &'a Value)> {
+    nodes_of(manifest, access, resource_type)
+        .into_iter()
+        .find(|(unique_id, node)| *unique_id == id || text(node, "name").as_deref() == Some(id))
+}
+
+fn description_html(manifest: &Value, description: Option<&str>) -> Option<String> {
+    description.map(|description| docs::description_html(manifest, description))
+}
+
+fn columns(manifest: &Value, node: &Value, hidden: Option<&HashSet<String>>) -> Vec<ResourceColumn> {
+    node.get("columns")
+        .and_then(|c| c.as_object())
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| hidden.is_none_or(|hidden| !hidden.contains(&name.to_lowercase())))
+        .map(|(name, col)| {
+            let description = text(col, "description").or_else(|| text(col, "comment"));
+            ResourceColumn {
+                name: text(col, "name").unwrap_or_else(|| name.clone()),
+                data_type: text(col, "type").or_else(|| text(col, "data_type")),
+                description_html: description_html(manifest, description.as_deref()),
+                description,
+            }
+        })
+        .collect()
+}
+
+/// Builds seeds and snapshots with their lineage; loaded once per request.
+struct Resources<'a> {
+    manifest: &'a Value,
+    graph: CatalogGraph,
+    access: Access,
+    hidden_columns: HashMap<String, HashSet<String>>,
+}
+
+impl<'a> Resources<'a> {
+    fn load(manifest: &'a Value, access: Access) -> Self {
+        Resources {
+            manifest,
+            graph: CatalogGraph::build_lineage(&load_all_manifests()),
+            hidden_columns: access.hidden_columns(),
+            access,
+        }
+    }
+
+    fn visible(&self, ids: &[String]) -> Vec<String> {
+        let mut ids: Vec<String> = ids
+            .iter()
+            .filter(|id| self.graph.nodes.get(*id).is_none_or(|n| self.access.can_see(&n.node)))
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn seed(&self, unique_id: &str, node: &Value) -> Seed {
+        Seed {
+            unique_id: unique_id.to_string(),
+            name: text(node, "name").unwrap_or_default(),
+            description: text(node, "description"),
+            description_html: description_html(self.manifest, text(node, "description").as_deref()),
+            relation_name: text(node, "relation_name"),
+            original_file_path: text(node, "original_file_path"),
+            delimiter: config_text(node, "delimiter").unwrap_or_else(|| ",".to_string()),
+            column_types: config_value(node, "column_types")
+                .and_then(|types| types.as_object().cloned())
+                .into_iter()
+                .flatten()
+                .filter_map(|(column, data_type)| Some((column, data_type.as_str()?.to_string())))
+                .collect(),
+            quote_columns: config_value(node, "quote_columns").and_then(|q| q.as_bool()),
+            columns: columns(self.manifest, node, self.hidden_columns.get(unique_id)),
+            children: self.visible(self.graph.children(unique_id)),
+        }
+    }
+
+    fn snapshot(&self, unique_id: &str, node: &Value) -> DbtSnapshot {
+        // dbt 1.9 replaced `invalidate_hard_deletes` with `hard_deletes`
+        let hard_deletes = config_text(node, "hard_deletes").or_else(|| {
+            config_value(node, "invalidate_hard_deletes")
+                .and_then(|v| v.as_bool())
+                .map(|invalidate| if invalidate { "invalidate" } else { "ignore" }.to_string())
+        });
+        DbtSnapshot {
+            unique_id: unique_id.to_string(),
+            name: text(node, "name").unwrap_or_default(),
+            description: text(node, "description"),
+            description_html: description_html(self.manifest, text(node, "description").as_deref()),
+            relation_name: text(node, "relation_name"),
+            original_file_path: text(node, "original_file_path"),
+            strategy: config_text(node, "strategy"),
+            unique_key: config_value(node, "unique_key"),
+            updated_at: config_text(node, "updated_at"),
+            check_cols: config_value(node, "check_cols"),
+            hard_deletes,
+            columns: columns(self.manifest, node, self.hidden_columns.get(unique_id)),
+            parents: self.visible(self.graph.parents(unique_id)),
+            children: self.visible(self.graph.children(unique_id)),
+        }
+    }
+}
+
+/// Seeds of the project with their config, columns and downstream nodes.
+#[utoipa::path(
+    get,
+    path = "/seeds",
+    tag = "resources",
+    responses(
+        (status = 200, body = Vec<Seed>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_seeds(Project(project): Project, access: Access) -> Result<Json<Vec<Seed>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let seeds = nodes_of(&manifest, &access, "seed");
+    let resources = Resources::load(&manifest, access);
+    Ok(Json(seeds.into_iter().map(|(id, node)| resources.seed(id, node)).collect()))
+}
+
+/// A seed by name or unique_id.
+#[utoipa::path(
+    get,
+    path = "/seeds/{id}",
+    tag = "resources",
+    params(ResourcePath),
+    responses(
+        (status = 200, body = Seed),
+        (status = 404, description = "Unknown or hidden seed", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_seed(
+    Project(project): Project,
+    access: Access,
+    Path(ResourcePath { id }): Path<ResourcePath>,
+) -> Result<Json<Seed>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, node) = find_resource(&manifest, &access, "seed", &id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Seed not found: {}", id)))?;
+    Ok(Json(Resources::load(&manifest, access).seed(unique_id, node)))
+}
+
+/// dbt snapshots of the project with their strategy, columns and lineage.
+/// (`/snapshots` lists the catalog's stored manifest snapshots.)
+#[utoipa::path(
+    get,
+    path = "/dbt_snapshots",
+    tag = "resources",
+    responses(
+        (status = 200, body = Vec<DbtSnapshot>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_dbt_snapshots(
+    Project(project): Project,
+    access: Access,
+) -> Result<Json<Vec<DbtSnapshot>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let snapshots = nodes_of(&manifest, &access, "snapshot");
+    let resources = Resources::load(&manifest, access);
+    Ok(Json(snapshots.into_iter().map(|(id, node)| resources.snapshot(id, node)).collect()))
+}
+
+/// A dbt snapshot by name or unique_id.
+#[utoipa::path(
+    get,
+    path = "/dbt_snapshots/{id}",
+    tag = "resources",
+    params(ResourcePath),
+    responses(
+        (status = 200, body = DbtSnapshot),
+        (status = 404, description = "Unknown or hidden snapshot", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_dbt_snapshot(
+    Project(project): Project,
+    access: Access,
+    Path(ResourcePath { id }): Path<ResourcePath>,
+) -> Result<Json<DbtSnapshot>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, node) = find_resource(&manifest, &access, "snapshot", &id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Snapshot not found: {}", id)))?;
+    Ok(Json(Resources::load(&manifest, access).snapshot(unique_id, node)))
+}
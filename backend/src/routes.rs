@@ -1,12 +1,87 @@
-use axum::{routing::get, Router};
+use axum::{routing::{delete, get, post}, Router};
+use utoipa_swagger_ui::SwaggerUi;
 use crate::lineage::get_lineage;
+use crate::audit::get_audit;
+use crate::annotations::{get_column_annotation, get_model_annotation, put_column_annotation, put_model_annotation};
 use crate::dbt::{get_models, get_model_details, get_model_docs, get_manifest};
+use crate::classification::get_classification;
+use crate::docs::{get_doc, get_docs, get_overview};
+use crate::drift::get_drift;
+use crate::glossary::{add_link, create_term, delete_link, delete_term, get_suggestions, get_term, list_terms, update_term};
+use crate::governance::get_owner_resources;
+use crate::graphql::{graphiql, graphql};
+use crate::health::get_health;
+use crate::lint::get_lint;
+use crate::macros::{get_macro, get_macros};
+use crate::history::{get_freshness, get_model_runs, list_snapshots};
+use crate::openapi;
+use crate::mesh::{get_access_warnings, get_groups, get_model_versions};
+use crate::preview::{get_model_preview, get_seed_preview};
+use crate::profiling::{get_model_profile, run_profiling};
+use crate::projects::list_projects;
+use crate::resources::{get_dbt_snapshot, get_dbt_snapshots, get_seed, get_seeds};
+use crate::search::search;
+use crate::unit_tests::get_unit_tests;
+use crate::semantic::{get_metric, get_metrics, get_saved_queries, get_semantic_model, get_semantic_models};
 
 pub fn init_routes() -> Router {
+    Router::new()
+        // Un-prefixed routes serve the default project
+        .merge(project_routes())
+        .route("/projects", get(list_projects))
+        .route("/audit", get(get_audit))
+        // The glossary is shared by all projects; links name their project
+        .route("/glossary", get(list_terms).post(create_term))
+        .route("/glossary/:term", get(get_term).put(update_term).delete(delete_term))
+        .route("/glossary/:term/links", post(add_link))
+        .route("/glossary/:term/links/:link_id", delete(delete_link))
+        .route("/glossary/:term/suggestions", get(get_suggestions))
+        // One graph over every project; GraphiQL on GET, queries on POST
+        .route("/graphql", get(graphiql).post(graphql))
+        .nest("/projects/:project", project_routes())
+        // Generated OpenAPI document and the bundled Swagger UI to browse it
+        .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", openapi::document()))
+}
+
+/// Routes available per project, under `/projects/:project`.
+fn project_routes() -> Router {
     Router::new()
         .route("/models", get(get_models))
         .route("/models/:id", get(get_model_details))
         .route("/model_docs/:id", get(get_model_docs))
         .route("/lineage/:start/:end", get(get_lineage))
         .route("/manifest", get(get_manifest))
-}
\ No newline at end of file
+        .route("/groups", get(get_groups))
+        .route("/access_warnings", get(get_access_warnings))
+        .route("/models/:id/versions", get(get_model_versions))
+        .route("/owners/:owner", get(get_owner_resources))
+        .route("/classification", get(get_classification))
+        .route("/models/:id/preview", get(get_model_preview))
+        .route("/models/:id/profile", get(get_model_profile))
+        .route("/profiles/run", post(run_profiling))
+        .route("/drift", get(get_drift))
+        .route("/snapshots", get(list_snapshots))
+        .route("/models/:id/runs", get(get_model_runs))
+        .route("/models/:id/unit_tests", get(get_unit_tests))
+        .route("/freshness", get(get_freshness))
+        .route("/models/:id/annotations", get(get_model_annotation).put(put_model_annotation))
+        .route("/models/:id/columns/:col/annotations", get(get_column_annotation).put(put_column_annotation))
+        .route("/search", get(search))
+        .route("/lint", get(get_lint))
+        .route("/health", get(get_health))
+        .route("/semantic_models", get(get_semantic_models))
+        .route("/semantic_models/:id", get(get_semantic_model))
+        .route("/metrics", get(get_metrics))
+        .route("/metrics/:id", get(get_metric))
+        .route("/saved_queries", get(get_saved_queries))
+        .route("/macros", get(get_macros))
+        .route("/macros/:id", get(get_macro))
+        .route("/seeds", get(get_seeds))
+        .route("/seeds/:id", get(get_seed))
+        .route("/seeds/:id/preview", get(get_seed_preview))
+        .route("/dbt_snapshots", get(get_dbt_snapshots))
+        .route("/dbt_snapshots/:id", get(get_dbt_snapshot))
+        .route("/docs", get(get_docs))
+        .route("/docs/:id", get(get_doc))
+        .route("/overview", get(get_overview))
+}
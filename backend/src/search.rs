@@ -0,0 +1,161 @@
+use axum::{extract::Query, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::config::ProjectConfig;
+use crate::glossary;
+use crate::projects::{load_manifest, Project};
+use crate::rbac::Access;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchParams {
+    /// Case-insensitive search text.
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchHit {
+    /// Resource type (`model`, `source`, `seed`, `metric`, ...), `column`
+    /// or `glossary_term`.
+    pub kind: String,
+    pub name: String,
+    pub unique_id: Option<String>,
+    pub column: Option<String>,
+    pub description: Option<String>,
+    /// Higher is better: exact name > name prefix > name substring > description.
+    pub score: u32,
+}
+
+/// Score of `name`/`description` against a lowercased query; 0 is no match.
+fn score(query: &str, names: &[&str], description: &str) -> u32 {
+    let name_score = names
+        .iter()
+        .map(|name| {
+            let name = name.to_lowercase();
+            if name == query {
+                100
+            } else if name.starts_with(query) {
+                75
+            } else if name.contains(query) {
+                50
+            } else {
+                0
+            }
+        })
+        .max()
+        .unwrap_or(0);
+    if name_score > 0 {
+        name_score
+    } else if description.to_lowercase().contains(query) {
+        20
+    } else {
+        0
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Search models, sources, metrics, columns and glossary terms by name and description.
+#[utoipa::path(
+    get,
+    path = "/search",
+    tag = "search",
+    params(SearchParams),
+    responses(
+        (status = 200, body = Vec<SearchHit>),
+        (status = 400, description = "Empty query", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn search(
+    Project(project): Project,
+    access: Access,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<Vec<SearchHit>>, (StatusCode, String)> {
+    search_catalog(project, &access, &params.q, params.limit).map(Json)
+}
+
+/// Hits for `query` in a project and the glossary, best first.
+pub fn search_catalog(
+    project: &ProjectConfig,
+    access: &Access,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, (StatusCode, String)> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "q must not be empty".to_string()));
+    }
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+
+    let hidden_columns = access.hidden_columns();
+    let mut hits = vec![];
+    let entities = ["nodes", "sources", "metrics"]
+        .iter()
+        .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+        .flat_map(|section| section.iter())
+        .filter(|(_, node)| node.get("resource_type").and_then(|r| r.as_str()) != Some("test"))
+        .filter(|(_, node)| access.can_see(node));
+    for (unique_id, node) in entities {
+        let name = node.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+        let kind = node.get("resource_type").and_then(|r| r.as_str()).unwrap_or("node");
+        let description = node.get("description").and_then(|d| d.as_str()).unwrap_or_default();
+        let label = node.get("label").and_then(|l| l.as_str()).unwrap_or_default();
+        let node_score = score(&query, &[name, label], description);
+        if node_score > 0 {
+            hits.push(SearchHit {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                unique_id: Some(unique_id.clone()),
+                column: None,
+                description: non_empty(description),
+                score: node_score,
+            });
+        }
+
+        for (column_name, column) in node.get("columns").and_then(|c| c.as_object()).into_iter().flatten() {
+            if hidden_columns.get(unique_id).is_some_and(|hidden| hidden.contains(&column_name.to_lowercase())) {
+                continue;
+            }
+            let description = column.get("description").and_then(|d| d.as_str()).unwrap_or_default();
+            let column_score = score(&query, &[column_name], description);
+            if column_score > 0 {
+                hits.push(SearchHit {
+                    kind: "column".to_string(),
+                    name: name.to_string(),
+                    unique_id: Some(unique_id.clone()),
+                    column: Some(column_name.clone()),
+                    description: non_empty(description),
+                    score: column_score,
+                });
+            }
+        }
+    }
+
+    let terms = glossary::load_terms().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    for term in terms {
+        let names: Vec<&str> = std::iter::once(term.name.as_str()).chain(term.synonyms.iter().map(String::as_str)).collect();
+        let term_score = score(&query, &names, &term.definition);
+        if term_score > 0 {
+            hits.push(SearchHit {
+                kind: "glossary_term".to_string(),
+                name: term.name.clone(),
+                unique_id: None,
+                column: None,
+                description: non_empty(&term.definition),
+                score: term_score,
+            });
+        }
+    }
+
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)).then_with(|| a.column.cmp(&b.column)));
+    hits.truncate(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT));
+    Ok(hits)
+}
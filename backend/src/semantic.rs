@@ -0,0 +1,426 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::docs;
+use crate::graph::CatalogGraph;
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+
+#[derive(Serialize, ToSchema)]
+pub struct SemanticModel {
+    pub unique_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Rendered to sanitized HTML, with `doc()` references resolved.
+    pub description_html: Option<String>,
+    /// The dbt model the semantic model is defined on, when the caller may see it.
+    pub model: Option<String>,
+    pub relation_name: Option<String>,
+    pub primary_entity: Option<String>,
+    pub agg_time_dimension: Option<String>,
+    pub entities: Vec<Entity>,
+    pub measures: Vec<Measure>,
+    pub dimensions: Vec<Dimension>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Entity {
+    pub name: String,
+    /// `primary`, `unique`, `foreign` or `natural`.
+    #[serde(rename = "type")]
+    pub entity_type: Option<String>,
+    pub expr: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Measure {
+    pub name: String,
+    pub agg: Option<String>,
+    pub expr: Option<String>,
+    pub description: Option<String>,
+    /// Semantic model defining the measure, when known.
+    pub semantic_model: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Dimension {
+    pub name: String,
+    /// `categorical` or `time`.
+    #[serde(rename = "type")]
+    pub dimension_type: Option<String>,
+    pub time_granularity: Option<String>,
+    pub expr: Option<String>,
+    pub description: Option<String>,
+    pub semantic_model: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Metric {
+    pub unique_id: String,
+    pub name: String,
+    pub label: Option<String>,
+    pub description: Option<String>,
+    /// Rendered to sanitized HTML, with `doc()` references resolved.
+    pub description_html: Option<String>,
+    /// `simple`, `ratio`, `cumulative`, `derived` or `conversion`.
+    #[serde(rename = "type")]
+    pub metric_type: Option<String>,
+    /// Derived metrics' expression over their input metrics.
+    pub expr: Option<String>,
+    pub measures: Vec<Measure>,
+    /// Metrics a derived or ratio metric is computed from.
+    pub input_metrics: Vec<String>,
+    /// Where filters of the metric and of its inputs.
+    pub filters: Vec<String>,
+    /// Dimensions of the semantic models the metric relies on.
+    pub dimensions: Vec<Dimension>,
+    /// Semantic models the metric relies on that the caller may see.
+    pub semantic_models: Vec<String>,
+    /// dbt models under those semantic models.
+    pub models: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SavedQuery {
+    pub unique_id: String,
+    pub name: String,
+    pub label: Option<String>,
+    pub description: Option<String>,
+    /// Rendered to sanitized HTML, with `doc()` references resolved.
+    pub description_html: Option<String>,
+    pub metrics: Vec<String>,
+    pub group_by: Vec<String>,
+    pub filters: Vec<String>,
+    /// Names of the tables/views the query is exported as.
+    pub exports: Vec<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub struct SemanticPath {
+    /// Name or unique_id.
+    id: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MetricParams {
+    /// Only metrics relying on this dbt model (name or unique_id).
+    model: Option<String>,
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(String::from)
+}
+
+fn description_html(manifest: &Value, node: &Value) -> Option<String> {
+    text(node, "description").map(|description| docs::description_html(manifest, &description))
+}
+
+fn items<'a>(node: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    node.get(key).and_then(|v| v.as_array()).into_iter().flatten()
+}
+
+fn strings(node: &Value, key: &str) -> Vec<String> {
+    items(node, key).filter_map(|v| v.as_str().map(String::from)).collect()
+}
+
+/// `where_sql_template`s of a `filter`/`where` object.
+fn where_filters(filter: Option<&Value>) -> Vec<String> {
+    filter.map_or(vec![], |filter| {
+        items(filter, "where_filters").filter_map(|f| text(f, "where_sql_template")).collect()
+    })
+}
+
+fn section<'a>(manifest: &'a Value, name: &str) -> impl Iterator<Item = (&'a String, &'a Value)> {
+    manifest.get(name).and_then(|s| s.as_object()).into_iter().flatten()
+}
+
+/// An entry of `section` by unique_id or name.
+fn find<'a>(manifest: &'a Value, name: &str, id: &str) -> Option<(&'a String, &'a Value)> {
+    section(manifest, name).find(|(unique_id, node)| *unique_id == id || text(node, "name").as_deref() == Some(id))
+}
+
+fn measures_of(semantic_model: &Value) -> Vec<Measure> {
+    let owner = text(semantic_model, "name");
+    items(semantic_model, "measures")
+        .map(|m| Measure {
+            name: text(m, "name").unwrap_or_default(),
+            agg: text(m, "agg"),
+            expr: text(m, "expr"),
+            description: text(m, "description"),
+            semantic_model: owner.clone(),
+        })
+        .collect()
+}
+
+fn dimensions_of(semantic_model: &Value) -> Vec<Dimension> {
+    let owner = text(semantic_model, "name");
+    items(semantic_model, "dimensions")
+        .map(|d| Dimension {
+            name: text(d, "name").unwrap_or_default(),
+            dimension_type: text(d, "type"),
+            time_granularity: d.get("type_params").and_then(|p| text(p, "time_granularity")),
+            expr: text(d, "expr"),
+            description: text(d, "description"),
+            semantic_model: owner.clone(),
+        })
+        .collect()
+}
+
+/// Whether the caller may see a graph node; ids outside the graph are kept.
+fn visible(graph: &CatalogGraph, access: &Access, unique_id: &str) -> bool {
+    graph.nodes.get(unique_id).is_none_or(|n| access.can_see(&n.node))
+}
+
+fn semantic_model(
+    graph: &CatalogGraph,
+    manifest: &Value,
+    access: &Access,
+    unique_id: &str,
+    node: &Value,
+) -> SemanticModel {
+    let defaults = node.get("defaults");
+    SemanticModel {
+        unique_id: unique_id.to_string(),
+        name: text(node, "name").unwrap_or_default(),
+        description: text(node, "description"),
+        description_html: description_html(manifest, node),
+        model: graph.parents(unique_id).first().filter(|id| visible(graph, access, id)).cloned(),
+        relation_name: node.get("node_relation").and_then(|r| text(r, "relation_name")),
+        primary_entity: text(node, "primary_entity"),
+        agg_time_dimension: defaults.and_then(|d| text(d, "agg_time_dimension")),
+        entities: items(node, "entities")
+            .map(|e| Entity {
+                name: text(e, "name").unwrap_or_default(),
+                entity_type: text(e, "type"),
+                expr: text(e, "expr"),
+                description: text(e, "description"),
+            })
+            .collect(),
+        measures: measures_of(node),
+        dimensions: dimensions_of(node),
+    }
+}
+
+/// Resolve a metric's measures, filters and semantic models, following
+/// derived metrics through their inputs.
+fn metric(graph: &CatalogGraph, manifest: &Value, access: &Access, unique_id: &str, node: &Value) -> Metric {
+    let params = node.get("type_params").cloned().unwrap_or_default();
+
+    let mut semantic_models: Vec<String> = graph
+        .ancestors(unique_id)
+        .into_iter()
+        .filter(|id| {
+            graph.nodes.get(id).and_then(|n| n.node.get("resource_type")).and_then(|r| r.as_str()) == Some("semantic_model")
+        })
+        .filter(|id| visible(graph, access, id))
+        .collect();
+    semantic_models.sort();
+    let semantic_nodes: Vec<&Value> = semantic_models.iter().map(|id| &graph.nodes[id].node).collect();
+
+    // dbt resolves `input_measures` for every metric type; older manifests only have `measure`
+    let mut measure_refs: Vec<&Value> = items(&params, "input_measures").collect();
+    if measure_refs.is_empty() {
+        measure_refs.extend(params.get("measure"));
+    }
+    let measures = measure_refs
+        .iter()
+        .filter_map(|m| text(m, "name"))
+        .map(|name| {
+            semantic_nodes
+                .iter()
+                .flat_map(|sm| measures_of(sm))
+                .find(|m| m.name == name)
+                .unwrap_or(Measure { name, agg: None, expr: None, description: None, semantic_model: None })
+        })
+        .collect();
+
+    let mut filters = where_filters(node.get("filter"));
+    for input in ["measure", "numerator", "denominator"].iter().filter_map(|key| params.get(*key)).chain(items(&params, "metrics")) {
+        filters.extend(where_filters(input.get("filter")));
+    }
+
+    let mut models: Vec<String> = semantic_models
+        .iter()
+        .flat_map(|id| graph.parents(id))
+        .filter(|id| visible(graph, access, id))
+        .cloned()
+        .collect();
+    models.sort();
+    models.dedup();
+
+    Metric {
+        unique_id: unique_id.to_string(),
+        name: text(node, "name").unwrap_or_default(),
+        label: text(node, "label"),
+        description: text(node, "description"),
+        description_html: description_html(manifest, node),
+        metric_type: text(node, "type"),
+        expr: text(&params, "expr"),
+        measures,
+        // Ratio metrics divide two other metrics
+        input_metrics: ["numerator", "denominator"]
+            .iter()
+            .filter_map(|key| params.get(*key))
+            .chain(items(&params, "metrics"))
+            .filter_map(|m| text(m, "name"))
+            .collect(),
+        filters,
+        dimensions: semantic_nodes.iter().flat_map(|sm| dimensions_of(sm)).collect(),
+        semantic_models,
+        models,
+    }
+}
+
+fn saved_query(manifest: &Value, unique_id: &str, node: &Value) -> SavedQuery {
+    let params = node.get("query_params").cloned().unwrap_or_default();
+    SavedQuery {
+        unique_id: unique_id.to_string(),
+        name: text(node, "name").unwrap_or_default(),
+        label: text(node, "label"),
+        description: text(node, "description"),
+        description_html: description_html(manifest, node),
+        metrics: strings(&params, "metrics"),
+        group_by: strings(&params, "group_by"),
+        filters: where_filters(params.get("where")),
+        exports: items(node, "exports")
+            .filter_map(|e| e.get("config").and_then(|c| text(c, "alias")).or_else(|| text(e, "name")))
+            .collect(),
+    }
+}
+
+/// Semantic models of the project with their entities, measures and dimensions.
+#[utoipa::path(
+    get,
+    path = "/semantic_models",
+    tag = "semantic",
+    responses(
+        (status = 200, body = Vec<SemanticModel>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_semantic_models(
+    Project(project): Project,
+    access: Access,
+) -> Result<Json<Vec<SemanticModel>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let graph = CatalogGraph::build_lineage(&load_all_manifests());
+
+    let mut models: Vec<SemanticModel> = section(&manifest, "semantic_models")
+        .filter(|(_, node)| access.can_see(node))
+        .map(|(unique_id, node)| semantic_model(&graph, &manifest, &access, unique_id, node))
+        .collect();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(models))
+}
+
+/// A semantic model by name or unique_id.
+#[utoipa::path(
+    get,
+    path = "/semantic_models/{id}",
+    tag = "semantic",
+    params(SemanticPath),
+    responses(
+        (status = 200, body = SemanticModel),
+        (status = 404, description = "Unknown or hidden semantic model", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_semantic_model(
+    Project(project): Project,
+    access: Access,
+    Path(SemanticPath { id }): Path<SemanticPath>,
+) -> Result<Json<SemanticModel>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, node) = find(&manifest, "semantic_models", &id)
+        .filter(|(_, node)| access.can_see(node))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Semantic model not found: {}", id)))?;
+    let graph = CatalogGraph::build_lineage(&load_all_manifests());
+    Ok(Json(semantic_model(&graph, &manifest, &access, unique_id, node)))
+}
+
+/// Metrics of the project, traced through their semantic models to dbt models.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "semantic",
+    params(MetricParams),
+    responses(
+        (status = 200, body = Vec<Metric>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_metrics(
+    Project(project): Project,
+    access: Access,
+    Query(params): Query<MetricParams>,
+) -> Result<Json<Vec<Metric>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let graph = CatalogGraph::build_lineage(&load_all_manifests());
+    // An unknown model matches no metric rather than every one
+    let model = params.model.map(|model| graph.find(&model, &project.name).unwrap_or_default().to_string());
+
+    let mut metrics: Vec<Metric> = section(&manifest, "metrics")
+        .filter(|(_, node)| access.can_see(node))
+        .map(|(unique_id, node)| metric(&graph, &manifest, &access, unique_id, node))
+        .filter(|metric| model.as_ref().is_none_or(|model| metric.models.contains(model)))
+        .collect();
+    metrics.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(metrics))
+}
+
+/// A metric by name or unique_id.
+#[utoipa::path(
+    get,
+    path = "/metrics/{id}",
+    tag = "semantic",
+    params(SemanticPath),
+    responses(
+        (status = 200, body = Metric),
+        (status = 404, description = "Unknown or hidden metric", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_metric(
+    Project(project): Project,
+    access: Access,
+    Path(SemanticPath { id }): Path<SemanticPath>,
+) -> Result<Json<Metric>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let (unique_id, node) = find(&manifest, "metrics", &id)
+        .filter(|(_, node)| access.can_see(node))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Metric not found: {}", id)))?;
+    let graph = CatalogGraph::build_lineage(&load_all_manifests());
+    Ok(Json(metric(&graph, &manifest, &access, unique_id, node)))
+}
+
+/// Saved queries of the project with their metrics, group-bys and exports.
+#[utoipa::path(
+    get,
+    path = "/saved_queries",
+    tag = "semantic",
+    responses(
+        (status = 200, body = Vec<SavedQuery>),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_saved_queries(
+    Project(project): Project,
+    access: Access,
+) -> Result<Json<Vec<SavedQuery>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let mut queries: Vec<SavedQuery> = section(&manifest, "saved_queries")
+        .filter(|(_, node)| access.can_see(node))
+        .map(|(unique_id, node)| saved_query(&manifest, unique_id, node))
+        .collect();
+    queries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(queries))
+}
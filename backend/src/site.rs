@@ -0,0 +1,337 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::classification::classify;
+use crate::config::ProjectConfig;
+use crate::dbt::{model_docs, ModelColumn, ModelDocs};
+use crate::docs;
+use crate::export::{export_catalog, ExportedNode};
+use crate::glossary;
+use crate::graph::CatalogGraph;
+use crate::lineage::{model_lineage, Lineage};
+use crate::projects::load_all_manifests;
+use crate::rbac::Access;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;padding:0 1rem;color:#222}\
+a{color:#0b62a4}table{border-collapse:collapse;width:100%;margin:.5rem 0 1.5rem}\
+th,td{border-bottom:1px solid #ddd;padding:.35rem .5rem;text-align:left;vertical-align:top}\
+th{background:#f5f5f5}pre{background:#f5f5f5;padding:1rem;overflow:auto}\
+.muted{color:#777}.tag{background:#eef;border-radius:3px;padding:0 .3rem;margin-right:.2rem}\
+#q{width:100%;padding:.5rem;font-size:1rem;margin-bottom:1rem}";
+
+const SEARCH_SCRIPT: &str = "const q=document.getElementById('q'),out=document.getElementById('results');\
+q.addEventListener('input',()=>{const t=q.value.trim().toLowerCase();out.innerHTML='';if(!t)return;\
+SEARCH_INDEX.filter(e=>(e.name+' '+(e.column||'')+' '+(e.description||'')).toLowerCase().includes(t)).slice(0,50)\
+.forEach(e=>{const li=document.createElement('li'),a=document.createElement(e.url?'a':'span');\
+if(e.url)a.href=e.url;a.textContent=e.name+(e.column?'.'+e.column:'');li.append(a,' ('+e.kind+')');\
+if(e.description)li.append(' - '+e.description);out.append(li);});});";
+
+/// One entry of `search_index.json`, searched client-side by `index.html`.
+#[derive(Serialize)]
+struct SearchEntry {
+    /// Resource type, `column` or `glossary_term`.
+    kind: String,
+    name: String,
+    column: Option<String>,
+    project: Option<String>,
+    description: Option<String>,
+    /// Page relative to the site root.
+    url: Option<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<p><a href=\"{}index.html\">Catalog</a></p>\n{}\n</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        root,
+        body
+    )
+}
+
+/// Path of a node's page relative to the site root.
+fn node_path(project: &str, unique_id: &str) -> String {
+    format!("{}/{}.html", project, unique_id)
+}
+
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    if rows.is_empty() {
+        return "<p class=\"muted\">None</p>".to_string();
+    }
+    let head: String = headers.iter().map(|h| format!("<th>{}</th>", h)).collect();
+    let body: String = rows
+        .into_iter()
+        .map(|row| format!("<tr>{}</tr>", row.into_iter().map(|cell| format!("<td>{}</td>", cell)).collect::<String>()))
+        .collect();
+    format!("<table><tr>{}</tr>{}</table>", head, body)
+}
+
+fn tags(tags: &[String]) -> String {
+    tags.iter().map(|t| format!("<span class=\"tag\">{}</span>", escape(t))).collect()
+}
+
+fn opt(value: &Option<String>) -> String {
+    value.as_deref().map_or_else(|| "<span class=\"muted\">-</span>".to_string(), escape)
+}
+
+/// Links between pages, which all sit one directory below the root.
+struct Links<'a> {
+    projects: &'a HashMap<String, String>,
+    names: &'a HashMap<String, String>,
+}
+
+impl Links<'_> {
+    fn to(&self, unique_id: &str) -> String {
+        let name = self.names.get(unique_id).map_or(unique_id, String::as_str);
+        match self.projects.get(unique_id) {
+            Some(project) => format!("<a href=\"../{}\">{}</a>", node_path(project, unique_id), escape(name)),
+            None => escape(name),
+        }
+    }
+
+    fn list(&self, ids: &[String]) -> String {
+        ids.iter().map(|id| self.to(id)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn render_lineage(graph: &CatalogGraph, links: &Links, unique_id: &str, lineage: &Lineage) -> String {
+    let upstream = graph.ancestors(unique_id);
+    let downstream = graph.descendants(unique_id);
+    let rows = lineage
+        .models
+        .iter()
+        .map(|m| {
+            let direction = if m.unique_id == unique_id {
+                "this model"
+            } else if upstream.contains(&m.unique_id) {
+                "upstream"
+            } else if downstream.contains(&m.unique_id) {
+                "downstream"
+            } else {
+                "-"
+            };
+            let node = if m.redacted { "<span class=\"muted\">Redacted</span>".to_string() } else { links.to(&m.unique_id) };
+            vec![node, direction.to_string(), escape(&m.project), opt(&m.materialization), links.list(&m.depends_on.nodes)]
+        })
+        .collect();
+    table(&["Node", "Direction", "Project", "Materialized", "Depends on"], rows)
+}
+
+fn render_model(docs: &ModelDocs, lineage_html: &str, project: &str, unique_id: &str) -> String {
+    let general = &docs.general;
+    let governance = &docs.governance;
+    let facts = table(
+        &["Field", "Value"],
+        vec![
+            vec!["Project".to_string(), escape(project)],
+            vec!["Unique ID".to_string(), escape(unique_id)],
+            vec!["Materialized".to_string(), escape(&general.materialized)],
+            vec!["Relation".to_string(), escape(&docs.sql.relation_name)],
+            vec!["Access".to_string(), opt(&general.access)],
+            vec!["Group".to_string(), opt(&general.group)],
+            vec!["Version".to_string(), opt(&general.version.as_ref().map(|v| v.to_string()))],
+            vec!["Primary keys".to_string(), escape(&general.primary_keys.join(", "))],
+            vec!["Tags".to_string(), tags(&general.tags)],
+            vec!["Owners".to_string(), escape(&governance.owners.join(", "))],
+            vec!["Team".to_string(), opt(&governance.team)],
+            vec!["Domain".to_string(), opt(&governance.domain)],
+            vec!["Tier".to_string(), opt(&governance.tier)],
+            vec!["Glossary terms".to_string(), escape(&general.glossary_terms.join(", "))],
+            vec!["Health".to_string(), opt(&docs.health.as_ref().map(|h| format!("{} / 100", h.score)))],
+        ],
+    );
+    let annotation = general
+        .annotation
+        .as_ref()
+        .map(|a| format!("<h2>Notes</h2><pre>{}</pre>", escape(&a.body)))
+        .unwrap_or_default();
+    let columns = docs
+        .columns
+        .iter()
+        .map(|column| match column {
+            ModelColumn::Documented(col) => vec![
+                escape(&col.name),
+                escape(&col.data_type),
+                col.description_html.clone(),
+                col.classification.as_ref().map(|c| tags(&c.labels.iter().cloned().collect::<Vec<_>>())).unwrap_or_default(),
+                escape(&col.glossary_terms.join(", ")),
+            ],
+            ModelColumn::Redacted(col) => vec![
+                escape(&col.name),
+                "<span class=\"muted\">Redacted</span>".to_string(),
+                String::new(),
+                col.classification.as_ref().map(|c| tags(&c.labels.iter().cloned().collect::<Vec<_>>())).unwrap_or_default(),
+                String::new(),
+            ],
+        })
+        .collect();
+
+    let body = format!(
+        "<h1>{}</h1>\n<div>{}</div>\n{}\n{}\n<h2>Columns</h2>\n{}\n<h2>Lineage</h2>\n{}\n<h2>SQL</h2>\n<pre>{}</pre>",
+        escape(&general.name),
+        general.description_html,
+        facts,
+        annotation,
+        table(&["Column", "Type", "Description", "Classification", "Glossary terms"], columns),
+        lineage_html,
+        escape(&docs.sql.raw_code),
+    );
+    page(&general.name, "../", &body)
+}
+
+/// A description as sanitized HTML, with doc block references resolved.
+fn description(manifest: &Value, description: &Option<String>) -> String {
+    description.as_deref().map_or_else(|| opt(&None), |d| docs::description_html(manifest, d))
+}
+
+fn render_node(node: &ExportedNode, manifest: &Value, links: &Links, project: &str) -> String {
+    let facts = table(
+        &["Field", "Value"],
+        vec![
+            vec!["Type".to_string(), escape(&node.resource_type)],
+            vec!["Project".to_string(), escape(project)],
+            vec!["Unique ID".to_string(), escape(&node.unique_id)],
+            vec!["Materialized".to_string(), opt(&node.materialized)],
+            vec!["Tags".to_string(), tags(&node.tags)],
+            vec!["Owners".to_string(), escape(&node.governance.owners.join(", "))],
+            vec!["Team".to_string(), opt(&node.governance.team)],
+            vec!["Upstream".to_string(), links.list(&node.parents)],
+            vec!["Downstream".to_string(), links.list(&node.children)],
+        ],
+    );
+    let columns = node
+        .columns
+        .iter()
+        .map(|col| vec![escape(&col.name), opt(&col.data_type), description(manifest, &col.description)])
+        .collect();
+    let body = format!(
+        "<h1>{}</h1>\n<div>{}</div>\n{}\n<h2>Columns</h2>\n{}",
+        escape(&node.name),
+        description(manifest, &node.description),
+        facts,
+        table(&["Column", "Type", "Description"], columns),
+    );
+    page(&node.name, "../", &body)
+}
+
+fn write(dir: &Path, relative: &str, content: impl AsRef<[u8]>) -> Result<(), String> {
+    let path = dir.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize export: {}", e))
+}
+
+/// Render `only` (or every project) into `dir` as a static site: one HTML
+/// page per resource next to the JSON it was rendered from (model docs and
+/// lineage as the API serves them), `catalog.json` and a client-side search
+/// index. Returns the number of pages written.
+pub fn export_site(dir: &Path, only: Option<&ProjectConfig>, access: &Access) -> Result<usize, String> {
+    let manifests = load_all_manifests();
+    let graph = CatalogGraph::build_lineage(&manifests);
+    let classifications = classify(&graph);
+    let export = export_catalog(only, access);
+
+    let mut node_projects = HashMap::new();
+    let mut node_names = HashMap::new();
+    for project in &export.projects {
+        for node in &project.nodes {
+            node_projects.insert(node.unique_id.clone(), project.name.clone());
+            node_names.insert(node.unique_id.clone(), node.name.clone());
+        }
+    }
+    let links = Links { projects: &node_projects, names: &node_names };
+
+    let mut pages = 0;
+    let mut index = vec![];
+    let mut listing = String::new();
+    for project in &export.projects {
+        let Some((config, manifest)) = manifests.iter().find(|(config, _)| config.name == project.name) else {
+            continue;
+        };
+        let mut rows = vec![];
+        for node in project.nodes.iter().filter(|n| !matches!(n.resource_type.as_str(), "test" | "unit_test")) {
+            let path = node_path(&project.name, &node.unique_id);
+            let model = (node.resource_type == "model")
+                .then(|| manifest.get("nodes").and_then(|n| n.get(&node.unique_id)))
+                .flatten();
+            let html = match model {
+                Some(model) => {
+                    let docs = model_docs(config, access, manifest, &graph, &classifications, model);
+                    let lineage = model_lineage(&graph, access, &node.unique_id);
+                    let lineage_html = render_lineage(&graph, &links, &node.unique_id, &lineage);
+                    write(dir, &format!("{}/{}.json", project.name, node.unique_id), json(&docs)?)?;
+                    write(dir, &format!("{}/{}.lineage.json", project.name, node.unique_id), json(&lineage)?)?;
+                    render_model(&docs, &lineage_html, &project.name, &node.unique_id)
+                }
+                None => {
+                    write(dir, &format!("{}/{}.json", project.name, node.unique_id), json(node)?)?;
+                    render_node(node, manifest, &links, &project.name)
+                }
+            };
+            write(dir, &path, html)?;
+            pages += 1;
+
+            index.push(SearchEntry {
+                kind: node.resource_type.clone(),
+                name: node.name.clone(),
+                column: None,
+                project: Some(project.name.clone()),
+                description: node.description.clone(),
+                url: Some(path.clone()),
+            });
+            for column in &node.columns {
+                index.push(SearchEntry {
+                    kind: "column".to_string(),
+                    name: node.name.clone(),
+                    column: Some(column.name.clone()),
+                    project: Some(project.name.clone()),
+                    description: column.description.clone(),
+                    url: Some(path.clone()),
+                });
+            }
+            rows.push(vec![
+                format!("<a href=\"{}\">{}</a>", path, escape(&node.name)),
+                escape(&node.resource_type),
+                opt(&node.description),
+            ]);
+        }
+        listing.push_str(&format!("<h2>{}</h2>\n{}\n", escape(&project.name), table(&["Name", "Type", "Description"], rows)));
+    }
+
+    for term in glossary::load_terms()? {
+        index.push(SearchEntry {
+            kind: "glossary_term".to_string(),
+            name: term.name,
+            column: None,
+            project: None,
+            description: Some(term.definition).filter(|d| !d.is_empty()),
+            url: None,
+        });
+    }
+
+    let index_json = serde_json::to_string(&index).map_err(|e| format!("Failed to serialize search index: {}", e))?;
+    write(dir, "search_index.json", &index_json)?;
+    // A script rather than a fetch, so the site also works from file://
+    write(dir, "search_index.js", format!("const SEARCH_INDEX={};\n", index_json))?;
+    write(dir, "catalog.json", json(&export)?)?;
+    let body = format!(
+        "<h1>Data Catalog</h1>\n<p class=\"muted\">Exported {}</p>\n<input id=\"q\" placeholder=\"Search models, columns and glossary terms\">\n<ul id=\"results\"></ul>\n{}\n<script src=\"search_index.js\"></script>\n<script>{}</script>",
+        escape(&export.exported_at),
+        listing,
+        SEARCH_SCRIPT
+    );
+    write(dir, "index.html", page("Data Catalog", "", &body))?;
+    Ok(pages + 1)
+}
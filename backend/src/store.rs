@@ -0,0 +1,464 @@
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::config::{self, ProjectConfig};
+use crate::drift::load_drift_events;
+use crate::projects::load_manifest;
+
+static STORE: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+/// Schema migrations, applied in order at startup. `PRAGMA user_version`
+/// records how many have run; only ever append to this list.
+const MIGRATIONS: &[&str] = &[r#"
+CREATE TABLE manifest_snapshots (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    invocation_id TEXT NOT NULL,
+    generated_at TEXT,
+    dbt_version TEXT,
+    captured_at TEXT NOT NULL,
+    node_count INTEGER NOT NULL,
+    manifest TEXT NOT NULL,
+    UNIQUE (project, invocation_id)
+);
+
+CREATE TABLE run_results (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    invocation_id TEXT NOT NULL,
+    command TEXT,
+    generated_at TEXT,
+    unique_id TEXT NOT NULL,
+    status TEXT,
+    execution_time REAL,
+    failures INTEGER,
+    message TEXT,
+    UNIQUE (project, invocation_id, unique_id)
+);
+CREATE INDEX run_results_node ON run_results (project, unique_id);
+
+CREATE TABLE source_freshness (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    invocation_id TEXT NOT NULL,
+    generated_at TEXT,
+    unique_id TEXT NOT NULL,
+    status TEXT,
+    max_loaded_at TEXT,
+    snapshotted_at TEXT,
+    age_seconds REAL,
+    UNIQUE (project, invocation_id, unique_id)
+);
+CREATE INDEX source_freshness_node ON source_freshness (project, unique_id);
+
+CREATE TABLE profiles (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    unique_id TEXT NOT NULL,
+    relation_name TEXT NOT NULL,
+    profiled_at TEXT NOT NULL,
+    row_count TEXT NOT NULL,
+    columns TEXT NOT NULL
+);
+CREATE INDEX profiles_node ON profiles (project, unique_id);
+
+CREATE TABLE drift_events (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    detected_at TEXT NOT NULL,
+    unique_id TEXT NOT NULL,
+    name TEXT,
+    column_name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    previous_type TEXT,
+    current_type TEXT,
+    declared_type TEXT,
+    UNIQUE (project, detected_at, unique_id, column_name, kind)
+);
+
+CREATE TABLE annotations (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    unique_id TEXT NOT NULL,
+    column_name TEXT NOT NULL DEFAULT '',
+    body TEXT NOT NULL,
+    author TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (project, unique_id, column_name)
+);
+
+CREATE TABLE audit_events (
+    id INTEGER PRIMARY KEY,
+    occurred_at TEXT NOT NULL,
+    user TEXT,
+    method TEXT NOT NULL,
+    route TEXT NOT NULL,
+    project TEXT,
+    entity_id TEXT,
+    action TEXT NOT NULL,
+    status INTEGER NOT NULL
+);
+CREATE INDEX audit_events_time ON audit_events (occurred_at);
+"#, r#"
+ALTER TABLE annotations ADD COLUMN glossary_terms TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE annotations ADD COLUMN verified INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE annotations ADD COLUMN verified_by TEXT;
+ALTER TABLE annotations ADD COLUMN verified_at TEXT;
+"#, r#"
+CREATE TABLE glossary_terms (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    definition TEXT NOT NULL,
+    synonyms TEXT NOT NULL DEFAULT '[]',
+    owner TEXT,
+    status TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE glossary_links (
+    id INTEGER PRIMARY KEY,
+    term_id INTEGER NOT NULL REFERENCES glossary_terms (id),
+    project TEXT NOT NULL,
+    unique_id TEXT NOT NULL,
+    column_name TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    UNIQUE (term_id, project, unique_id, column_name)
+);
+CREATE INDEX glossary_links_entity ON glossary_links (project, unique_id);
+"#];
+
+/// Open (or create) the store and bring its schema up to date.
+pub fn init() -> Result<(), String> {
+    let path = &config::get().store.path;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let mut conn = Connection::open(path)
+        .map_err(|e| format!("Failed to open store {}: {}", path.display(), e))?;
+    // WAL lets the history endpoints read while an import is writing
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .and_then(|_| conn.pragma_update(None, "foreign_keys", true))
+        .map_err(|e| format!("Failed to configure store {}: {}", path.display(), e))?;
+    migrate(&mut conn).map_err(|e| format!("Failed to migrate store {}: {}", path.display(), e))?;
+
+    STORE.set(Mutex::new(conn)).map_err(|_| "store::init called twice".to_string())
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+        info!("Applied store migration {}", i + 1);
+    }
+    Ok(())
+}
+
+/// Run `f` against the store connection.
+pub fn with_conn<T>(f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let store = STORE.get().expect("store::init must run before the store is used");
+    // A panic while holding the lock leaves nothing half-written behind
+    // thanks to transactions, so a poisoned lock is still usable.
+    let mut conn = store.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut conn).map_err(|e| format!("Store error: {}", e))
+}
+
+fn str_at<'a>(value: &'a Value, path: &[&str]) -> Option<&'a str> {
+    path.iter().try_fold(value, |v, key| v.get(key))?.as_str()
+}
+
+fn read_artifact(path: &Path) -> Option<Value> {
+    if !path.exists() {
+        return None;
+    }
+    match fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string())) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("Skipping unreadable artifact {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+/// An artifact with the id its rows are stored under: the dbt invocation, or
+/// the generation time for artifacts without one. Rows are unique per id, so
+/// artifacts with neither are skipped instead of overwriting each other.
+fn artifact_key<'a>(project: &ProjectConfig, file: &str, artifact: &'a Value) -> Option<(&'a Value, &'a str)> {
+    let key = str_at(artifact, &["metadata", "invocation_id"])
+        .or_else(|| str_at(artifact, &["metadata", "generated_at"]))
+        .filter(|key| !key.is_empty());
+    if key.is_none() {
+        warn!("Skipping {} of project '{}': it has no invocation_id or generated_at", file, project.name);
+    }
+    key.map(|key| (artifact, key))
+}
+
+/// Import a project's enriched manifest, `run_results.json`, `sources.json`
+/// and drift log. Rows already present are skipped, so this is safe to repeat.
+pub fn ingest_project(project: &ProjectConfig) -> Result<(), String> {
+    let manifest = load_manifest(project).ok();
+    let run_results = read_artifact(&project.target_dir().join("run_results.json"));
+    let freshness = read_artifact(&project.target_dir().join("sources.json"));
+    let drift_events = load_drift_events(project);
+    let keep_snapshots = config::get().store.keep_snapshots;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    with_conn(|conn| {
+        let tx = conn.transaction()?;
+
+        // Re-enriching the same dbt invocation is not a new snapshot
+        let snapshot = manifest.as_ref().and_then(|manifest| {
+            let invocation_id = str_at(manifest, &["metadata", "invocation_id"])
+                .or_else(|| str_at(manifest, &["metadata", "generated_at"]));
+            invocation_id.map(|invocation_id| (manifest, invocation_id))
+        });
+        if let Some((manifest, invocation_id)) = snapshot {
+            let generated_at = str_at(manifest, &["metadata", "generated_at"]);
+            let node_count = ["nodes", "sources"]
+                .iter()
+                .filter_map(|section| manifest.get(section).and_then(|s| s.as_object()))
+                .map(|section| section.len())
+                .sum::<usize>();
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO manifest_snapshots
+                 (project, invocation_id, generated_at, dbt_version, captured_at, node_count, manifest)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    project.name,
+                    invocation_id,
+                    generated_at,
+                    str_at(manifest, &["metadata", "dbt_version"]),
+                    now,
+                    node_count,
+                    manifest.to_string(),
+                ],
+            )?;
+            if inserted > 0 {
+                info!("Stored manifest snapshot {} for project '{}'", invocation_id, project.name);
+                tx.execute(
+                    "DELETE FROM manifest_snapshots WHERE project = ?1 AND id NOT IN
+                     (SELECT id FROM manifest_snapshots WHERE project = ?1 ORDER BY id DESC LIMIT ?2)",
+                    params![project.name, keep_snapshots],
+                )?;
+            }
+        }
+
+        if let Some((run_results, invocation_id)) = run_results.as_ref().and_then(|r| artifact_key(project, "run_results.json", r)) {
+            let generated_at = str_at(run_results, &["metadata", "generated_at"]);
+            let command = str_at(run_results, &["args", "which"]);
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO run_results
+                 (project, invocation_id, command, generated_at, unique_id, status, execution_time, failures, message)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for result in run_results.get("results").and_then(|r| r.as_array()).into_iter().flatten() {
+                let Some(unique_id) = str_at(result, &["unique_id"]) else { continue };
+                insert.execute(params![
+                    project.name,
+                    invocation_id,
+                    command,
+                    generated_at,
+                    unique_id,
+                    str_at(result, &["status"]),
+                    result.get("execution_time").and_then(|t| t.as_f64()),
+                    result.get("failures").and_then(|f| f.as_i64()),
+                    str_at(result, &["message"]),
+                ])?;
+            }
+        }
+
+        if let Some((freshness, invocation_id)) = freshness.as_ref().and_then(|f| artifact_key(project, "sources.json", f)) {
+            let generated_at = str_at(freshness, &["metadata", "generated_at"]);
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO source_freshness
+                 (project, invocation_id, generated_at, unique_id, status, max_loaded_at, snapshotted_at, age_seconds)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for result in freshness.get("results").and_then(|r| r.as_array()).into_iter().flatten() {
+                let Some(unique_id) = str_at(result, &["unique_id"]) else { continue };
+                insert.execute(params![
+                    project.name,
+                    invocation_id,
+                    generated_at,
+                    unique_id,
+                    str_at(result, &["status"]),
+                    str_at(result, &["max_loaded_at"]),
+                    str_at(result, &["snapshotted_at"]),
+                    result.get("max_loaded_at_time_ago_in_s").and_then(|a| a.as_f64()),
+                ])?;
+            }
+        }
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO drift_events
+                 (project, detected_at, unique_id, name, column_name, kind, previous_type, current_type, declared_type)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for event in &drift_events {
+                insert.execute(params![
+                    project.name,
+                    event.detected_at,
+                    event.unique_id,
+                    event.name,
+                    event.column,
+                    event.kind,
+                    event.previous_type,
+                    event.current_type,
+                    event.declared_type,
+                ])?;
+            }
+        }
+
+        tx.commit()
+    })
+}
+
+/// Delete run results, audit events and profiles recorded before `cutoff`
+/// (RFC 3339), keeping the latest profile of every relation. Returns the
+/// number of rows deleted.
+fn prune_history(conn: &Connection, cutoff: &str) -> rusqlite::Result<usize> {
+    let runs = conn.execute("DELETE FROM run_results WHERE generated_at < ?1", params![cutoff])?;
+    let audit = conn.execute("DELETE FROM audit_events WHERE occurred_at < ?1", params![cutoff])?;
+    let profiles = conn.execute(
+        "DELETE FROM profiles WHERE profiled_at < ?1
+         AND id NOT IN (SELECT max(id) FROM profiles GROUP BY project, unique_id)",
+        params![cutoff],
+    )?;
+    Ok(runs + audit + profiles)
+}
+
+/// Apply `store.retention_days`, if set.
+pub fn prune() -> Result<(), String> {
+    let retention_days = config::get().store.retention_days;
+    if retention_days == 0 {
+        return Ok(());
+    }
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(retention_days as i64)).to_rfc3339();
+    let deleted = with_conn(|conn| prune_history(conn, &cutoff))?;
+    if deleted > 0 {
+        info!("Pruned {} history rows older than {} days", deleted, retention_days);
+    }
+    Ok(())
+}
+
+/// Import every project and prune old history, logging failures.
+pub fn ingest_all() {
+    for project in &config::get().projects {
+        if let Err(err) = ingest_project(project) {
+            error!("Failed to import artifacts of project '{}': {}", project.name, err);
+        }
+    }
+    if let Err(err) = prune() {
+        error!("Failed to prune the store: {}", err);
+    }
+}
+
+/// Import artifacts at startup and then every `store.ingest_interval_secs`.
+pub fn spawn_ingester() {
+    let interval_secs = config::get().store.ingest_interval_secs;
+    tokio::spawn(async move {
+        loop {
+            if let Err(err) = tokio::task::spawn_blocking(ingest_all).await {
+                error!("Artifact import panicked: {}", err);
+            }
+            if interval_secs == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn migrate_applies_every_migration_once() {
+        let mut conn = migrated();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+
+        // A second run finds nothing to do instead of failing on existing tables
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+
+        let verified: i64 = conn
+            .query_row("SELECT count(*) FROM pragma_table_info('annotations') WHERE name = 'verified'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(verified, 1);
+    }
+
+    #[test]
+    fn migrate_resumes_from_the_recorded_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        let glossary_terms = |conn: &Connection| conn.query_row("SELECT count(*) FROM glossary_terms", [], |row| row.get::<_, i64>(0));
+        assert!(glossary_terms(&conn).is_err());
+
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(glossary_terms(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn prune_history_keeps_recent_rows_and_latest_profiles() {
+        let conn = migrated();
+        for (invocation, generated_at) in [("old", "2020-01-01T00:00:00Z"), ("new", "2030-01-01T00:00:00Z")] {
+            conn.execute(
+                "INSERT INTO run_results (project, invocation_id, generated_at, unique_id) VALUES ('p', ?1, ?2, 'model.p.a')",
+                params![invocation, generated_at],
+            )
+            .unwrap();
+        }
+        for (unique_id, profiled_at) in [("model.p.a", "2020-01-01"), ("model.p.a", "2020-02-01"), ("model.p.b", "2020-01-01")] {
+            conn.execute(
+                "INSERT INTO profiles (project, unique_id, relation_name, profiled_at, row_count, columns)
+                 VALUES ('p', ?1, 'r', ?2, '0', '{}')",
+                params![unique_id, profiled_at],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(prune_history(&conn, "2025-01-01T00:00:00Z").unwrap(), 2);
+        let runs: Vec<String> = conn
+            .prepare("SELECT invocation_id FROM run_results")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(runs, ["new"]);
+        let profiles: Vec<(String, String)> = conn
+            .prepare("SELECT unique_id, profiled_at FROM profiles ORDER BY unique_id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            profiles,
+            [("model.p.a".to_string(), "2020-02-01".to_string()), ("model.p.b".to_string(), "2020-01-01".to_string())]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use log::error;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::OnceLock;
+use utoipa::ToSchema;
+
+use crate::classification::classify;
+use crate::config::ProjectConfig;
+use crate::dbt::{find_model, ModelPath};
+use crate::graph::CatalogGraph;
+use crate::history::{self, RunResult};
+use crate::projects::{load_all_manifests, load_manifest, Project};
+use crate::rbac::Access;
+use crate::utils::CsvRecords;
+
+static INPUT_CALL: OnceLock<Regex> = OnceLock::new();
+
+/// Fixture rows as a table; values of CSV fixtures are strings.
+#[derive(Serialize, ToSchema)]
+pub struct FixtureTable {
+    /// Sensitive columns the caller may not read are left out.
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Rows given to or expected from a unit test, in any of dbt's formats.
+#[derive(Serialize, ToSchema)]
+pub struct Fixture {
+    /// `dict`, `csv` or `sql`.
+    pub format: String,
+    /// Fixture file named instead of inline rows.
+    pub fixture: Option<String>,
+    /// Unset for `sql` fixtures and fixture files that can't be found.
+    pub table: Option<FixtureTable>,
+    /// `sql` fixtures; `Redacted` for callers who may not read SQL.
+    pub sql: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UnitTestInput {
+    /// As written, e.g. `ref('stg_orders')`.
+    pub input: String,
+    /// The node the input resolves to; unset when the caller may not see it.
+    pub unique_id: Option<String>,
+    pub rows: Fixture,
+}
+
+/// A macro, var or env var overridden for the test.
+#[derive(Serialize, ToSchema)]
+pub struct UnitTestOverride {
+    /// `macro`, `var` or `env_var`.
+    pub kind: String,
+    pub name: String,
+    pub value: Value,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UnitTest {
+    pub unique_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// unique_id of the model under test.
+    pub model: String,
+    pub given: Vec<UnitTestInput>,
+    pub expect: Fixture,
+    pub overrides: Vec<UnitTestOverride>,
+    /// Latest ingested result; `status` is `pass`, `fail` or `error`.
+    pub latest_result: Option<RunResult>,
+}
+
+fn text(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(String::from)
+}
+
+/// Columns in order of first appearance, so rows that leave some out still line up.
+fn dict_table(rows: &[Value]) -> FixtureTable {
+    let mut columns: Vec<String> = vec![];
+    for row in rows.iter().filter_map(|r| r.as_object()) {
+        for column in row.keys() {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+    }
+    let rows = rows
+        .iter()
+        .map(|row| columns.iter().map(|c| row.get(c).cloned().unwrap_or(Value::Null)).collect())
+        .collect();
+    FixtureTable { columns, rows }
+}
+
+/// Drop the columns in `hidden` and their values.
+fn strip_columns(table: &mut FixtureTable, hidden: &HashSet<String>) {
+    let keep: Vec<bool> = table.columns.iter().map(|c| !hidden.contains(&c.to_lowercase())).collect();
+    let mut kept = keep.iter();
+    table.columns.retain(|_| *kept.next().unwrap_or(&true));
+    for row in &mut table.rows {
+        let mut kept = keep.iter();
+        row.retain(|_| *kept.next().unwrap_or(&true));
+    }
+}
+
+fn csv_table(content: &str) -> FixtureTable {
+    let mut records = CsvRecords::new(content, ',');
+    let columns = records.next().unwrap_or_default();
+    let rows = records.map(|record| record.into_iter().map(Value::String).collect()).collect();
+    FixtureTable { columns, rows }
+}
+
+/// Fixture files live in `tests/fixtures/` of the dbt project, as `<name>.csv` or `<name>.sql`.
+fn read_fixture(project: &ProjectConfig, name: &str, format: &str) -> Option<String> {
+    let path = project.project_dir.join("tests").join("fixtures").join(format!("{}.{}", name, format));
+    fs::read_to_string(path).ok()
+}
+
+/// `hidden` holds the columns of the node the rows stand in for that the caller may not read.
+fn fixture(project: &ProjectConfig, access: &Access, spec: &Value, hidden: Option<&HashSet<String>>) -> Fixture {
+    let format = text(spec, "format").unwrap_or_else(|| "dict".to_string());
+    let fixture = text(spec, "fixture");
+    let rows = spec.get("rows").filter(|r| !r.is_null()).cloned().or_else(|| {
+        let name = fixture.as_ref()?;
+        read_fixture(project, name, if format == "sql" { "sql" } else { "csv" }).map(Value::String)
+    });
+
+    let (mut table, sql) = match (format.as_str(), rows) {
+        ("sql", Some(Value::String(sql))) => {
+            (None, Some(if access.sees_raw_sql() { sql } else { "Redacted".to_string() }))
+        }
+        (_, Some(Value::Array(rows))) => (Some(dict_table(&rows)), None),
+        (_, Some(Value::String(csv))) => (Some(csv_table(&csv)), None),
+        _ => (None, None),
+    };
+    if let (Some(table), Some(hidden)) = (table.as_mut(), hidden) {
+        strip_columns(table, hidden);
+    }
+    Fixture { format, fixture, table, sql }
+}
+
+/// Resolve `ref('name')`, `ref('package', 'name')` or `source('source', 'table')`.
+fn resolve_input(graph: &CatalogGraph, project: &str, input: &str) -> Option<String> {
+    let call = INPUT_CALL
+        .get_or_init(|| Regex::new(r#"^\s*(ref|source)\s*\((.*)\)\s*$"#).expect("input call pattern is valid"));
+    let captures = call.captures(input)?;
+    let args: Vec<&str> = captures[2]
+        .split(',')
+        .map(|arg| arg.trim().trim_matches(|c| c == '\'' || c == '"'))
+        .filter(|arg| !arg.is_empty() && !arg.contains('='))
+        .collect();
+    match (&captures[1], args.as_slice()) {
+        ("ref", [.., name]) => graph.find(name, project).map(String::from),
+        ("source", [source, table]) => graph
+            .nodes
+            .iter()
+            .find(|(_, n)| {
+                n.node.get("resource_type").and_then(|r| r.as_str()) == Some("source")
+                    && n.node.get("source_name").and_then(|s| s.as_str()) == Some(*source)
+                    && n.node.get("name").and_then(|s| s.as_str()) == Some(*table)
+            })
+            .map(|(id, _)| id.clone()),
+        _ => None,
+    }
+}
+
+fn overrides(test: &Value) -> Vec<UnitTestOverride> {
+    let Some(overrides) = test.get("overrides") else {
+        return vec![];
+    };
+    [("macro", "macros"), ("var", "vars"), ("env_var", "env_vars")]
+        .iter()
+        .flat_map(|(kind, key)| {
+            overrides.get(*key).and_then(|o| o.as_object()).into_iter().flatten().map(|(name, value)| UnitTestOverride {
+                kind: kind.to_string(),
+                name: name.clone(),
+                value: value.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Unit tests of a model with their inputs, expected rows and latest result.
+#[utoipa::path(
+    get,
+    path = "/models/{id}/unit_tests",
+    tag = "models",
+    params(ModelPath),
+    responses(
+        (status = 200, body = Vec<UnitTest>),
+        (status = 404, description = "Unknown or hidden model", body = String),
+        (status = 503, description = "Manifest unavailable", body = String),
+    )
+)]
+pub async fn get_unit_tests(
+    Project(project): Project,
+    access: Access,
+    Path(ModelPath { id }): Path<ModelPath>,
+) -> Result<Json<Vec<UnitTest>>, (StatusCode, String)> {
+    let manifest = load_manifest(project).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    let model_id = find_model(&manifest, &id)
+        .filter(|model| access.can_see(model))
+        .and_then(|model| text(model, "unique_id"))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Model not found: {}", id)))?;
+    let graph = CatalogGraph::build(&load_all_manifests());
+    let hidden_columns =
+        if access.sees_sensitive_columns() { HashMap::new() } else { access.hidden_columns_of(&classify(&graph)) };
+    let visible = |unique_id: &String| graph.nodes.get(unique_id).is_some_and(|n| access.can_see(&n.node));
+
+    let mut tests: Vec<UnitTest> = manifest
+        .get("unit_tests")
+        .and_then(|u| u.as_object())
+        .into_iter()
+        .flatten()
+        .filter(|(_, test)| {
+            test.get("depends_on")
+                .and_then(|d| d.get("nodes"))
+                .and_then(|n| n.as_array())
+                .is_some_and(|nodes| nodes.iter().any(|n| n.as_str() == Some(model_id.as_str())))
+        })
+        .map(|(unique_id, test)| UnitTest {
+            unique_id: unique_id.clone(),
+            name: text(test, "name").unwrap_or_default(),
+            description: text(test, "description"),
+            model: model_id.clone(),
+            given: test
+                .get("given")
+                .and_then(|g| g.as_array())
+                .into_iter()
+                .flatten()
+                .map(|given| {
+                    let input = text(given, "input").unwrap_or_default();
+                    let unique_id = resolve_input(&graph, &project.name, &input);
+                    let hidden = unique_id.as_ref().and_then(|id| hidden_columns.get(id));
+                    UnitTestInput {
+                        rows: fixture(project, &access, given, hidden),
+                        unique_id: unique_id.filter(visible),
+                        input,
+                    }
+                })
+                .collect(),
+            expect: fixture(
+                project,
+                &access,
+                test.get("expect").unwrap_or(&Value::Null),
+                hidden_columns.get(&model_id),
+            ),
+            overrides: overrides(test),
+            latest_result: history::latest_run(project, unique_id).unwrap_or_else(|err| {
+                error!("Failed to load the latest result of {}: {}", unique_id, err);
+                None
+            }),
+        })
+        .collect();
+    tests.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Json(tests))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strip_columns_drops_hidden_columns_and_their_values() {
+        let mut table = dict_table(&[json!({"id": 1, "Email": "a@example.com", "total": 10}), json!({"id": 2})]);
+        strip_columns(&mut table, &HashSet::from(["email".to_string()]));
+        assert_eq!(table.columns, ["id", "total"]);
+        assert_eq!(table.rows, [vec![json!(1), json!(10)], vec![json!(2), Value::Null]]);
+    }
+}
@@ -1,10 +1,92 @@
 use std::fs;
+use std::iter::Peekable;
+use std::str::Chars;
 
 pub fn read_file(file_path: &str) -> Result<String, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(file_path)?;
     Ok(content)
 }
 
-pub fn log_message(message: &str) {
-    println!("[LOG]: {}", message);
-}
\ No newline at end of file
+/// Minimal RFC 4180 reader: quoted fields may hold delimiters, doubled
+/// quotes and newlines. Blank lines are skipped.
+pub struct CsvRecords<'a> {
+    chars: Peekable<Chars<'a>>,
+    delimiter: char,
+}
+
+impl<'a> CsvRecords<'a> {
+    pub fn new(content: &'a str, delimiter: char) -> Self {
+        CsvRecords { chars: content.trim_start_matches('\u{feff}').chars().peekable(), delimiter }
+    }
+}
+
+impl Iterator for CsvRecords<'_> {
+    type Item = Vec<String>;
+
+    fn next(&mut self) -> Option<Vec<String>> {
+        loop {
+            self.chars.peek()?;
+            let mut record = vec![];
+            let mut field = String::new();
+            let mut quoted = false;
+            while let Some(c) = self.chars.next() {
+                match c {
+                    '"' if quoted => {
+                        if self.chars.peek() == Some(&'"') {
+                            self.chars.next();
+                            field.push('"');
+                        } else {
+                            quoted = false;
+                        }
+                    }
+                    '"' if field.is_empty() => quoted = true,
+                    c if quoted => field.push(c),
+                    c if c == self.delimiter => record.push(std::mem::take(&mut field)),
+                    '\r' => {}
+                    '\n' => break,
+                    c => field.push(c),
+                }
+            }
+            if !record.is_empty() || !field.is_empty() {
+                record.push(field);
+                return Some(record);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(content: &str, delimiter: char) -> Vec<Vec<String>> {
+        CsvRecords::new(content, delimiter).collect()
+    }
+
+    #[test]
+    fn csv_records_split_on_the_delimiter() {
+        assert_eq!(records("id,name\r\n1,ada\n\n2,\n", ','), [vec!["id", "name"], vec!["1", "ada"], vec!["2", ""]]);
+        assert_eq!(records("id;name\n1;a,b", ';'), [vec!["id", "name"], vec!["1", "a,b"]]);
+    }
+
+    #[test]
+    fn csv_records_unquote_fields() {
+        assert_eq!(records("\"a,b\",\"say \"\"hi\"\"\",\"\"\n", ','), [vec!["a,b", "say \"hi\"", ""]]);
+        // Quotes inside an unquoted field are kept as written
+        assert_eq!(records("5\" pipe,x\n", ','), [vec!["5\" pipe", "x"]]);
+    }
+
+    #[test]
+    fn csv_records_keep_newlines_in_quoted_fields() {
+        assert_eq!(records("id,note\n1,\"line one\nline two\"\n2,x\n", ','), [
+            vec!["id", "note"],
+            vec!["1", "line one\nline two"],
+            vec!["2", "x"],
+        ]);
+    }
+
+    #[test]
+    fn csv_records_skip_a_byte_order_mark() {
+        assert_eq!(records("\u{feff}id,name\n1,ada\n", ','), [vec!["id", "name"], vec!["1", "ada"]]);
+    }
+}
@@ -0,0 +1,129 @@
+use log::info;
+use serde_json::Value;
+use serde_yaml::Value as YamlValue;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::config::ProjectConfig;
+use crate::utils::read_file;
+
+/// Failures talking to the warehouse, kept apart so handlers can map them to
+/// the right status code.
+pub enum WarehouseError {
+    /// The DuckDB file could not be located from the config or profiles.yml.
+    Unresolved(String),
+    TimedOut(u64),
+    Failed(String),
+}
+
+impl std::fmt::Display for WarehouseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WarehouseError::Unresolved(msg) => write!(f, "{}", msg),
+            WarehouseError::TimedOut(secs) => write!(f, "Warehouse query timed out after {} seconds", secs),
+            WarehouseError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+fn yaml_str<'a>(value: &'a YamlValue, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+/// The DuckDB database file for a project: the configured `duckdb_path`, or the
+/// `path` of the active duckdb output in profiles.yml.
+pub fn duckdb_path(project: &ProjectConfig) -> Result<PathBuf, WarehouseError> {
+    if let Some(path) = &project.duckdb_path {
+        return Ok(path.clone());
+    }
+    let unresolved = |msg: String| {
+        WarehouseError::Unresolved(format!("project '{}': {} (set duckdb_path in the config)", project.name, msg))
+    };
+
+    let profiles_path = [
+        project.profiles_dir.as_ref().map(|dir| dir.join("profiles.yml")),
+        Some(project.project_dir.join("profiles.yml")),
+        std::env::var_os("HOME").map(|home| Path::new(&home).join(".dbt").join("profiles.yml")),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|path| path.is_file())
+    .ok_or_else(|| unresolved("no profiles.yml found".to_string()))?;
+
+    let profiles: YamlValue = read_file(&profiles_path.display().to_string())
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_yaml::from_str(&content).map_err(|e| e.to_string()))
+        .map_err(|e| unresolved(format!("failed to read {}: {}", profiles_path.display(), e)))?;
+
+    let profile_name = match &project.profile {
+        Some(profile) => profile.clone(),
+        None => {
+            let dbt_project_path = project.project_dir.join("dbt_project.yml");
+            let dbt_project: YamlValue = read_file(&dbt_project_path.display().to_string())
+                .ok()
+                .and_then(|content| serde_yaml::from_str(&content).ok())
+                .ok_or_else(|| unresolved(format!("failed to read {}", dbt_project_path.display())))?;
+            yaml_str(&dbt_project, "profile")
+                .ok_or_else(|| unresolved("dbt_project.yml has no profile".to_string()))?
+                .to_string()
+        }
+    };
+
+    let profile = profiles
+        .get(profile_name.as_str())
+        .ok_or_else(|| unresolved(format!("profile '{}' not found in {}", profile_name, profiles_path.display())))?;
+    let target = project
+        .target
+        .as_deref()
+        .or_else(|| yaml_str(profile, "target"))
+        .ok_or_else(|| unresolved(format!("profile '{}' has no target", profile_name)))?;
+    let output = profile
+        .get("outputs")
+        .and_then(|outputs| outputs.get(target))
+        .ok_or_else(|| unresolved(format!("target '{}' not found in profile '{}'", target, profile_name)))?;
+
+    if yaml_str(output, "type") != Some("duckdb") {
+        return Err(unresolved(format!("target '{}' is not a duckdb target", target)));
+    }
+    let path = yaml_str(output, "path")
+        .ok_or_else(|| unresolved(format!("target '{}' has no path", target)))?;
+    if path.contains("{{") {
+        return Err(unresolved(format!("target '{}' path is templated", target)));
+    }
+
+    // dbt runs from the project directory, so relative paths are relative to it
+    Ok(project.project_dir.join(path))
+}
+
+/// Run one of the python warehouse scripts and parse the JSON it prints,
+/// killing it if it runs longer than `timeout_secs`.
+pub async fn run_script(script: &Path, args: &[String], timeout_secs: u64) -> Result<Value, WarehouseError> {
+    info!("Running warehouse script {} {}", script.display(), args.join(" "));
+    let child = Command::new("python3")
+        .arg(script)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| WarehouseError::Failed(format!("Failed to run {}: {}", script.display(), e)))?;
+
+    let output = tokio::time::timeout(Duration::from_secs(timeout_secs), child.wait_with_output())
+        .await
+        .map_err(|_| WarehouseError::TimedOut(timeout_secs))?
+        .map_err(|e| WarehouseError::Failed(format!("Failed to run {}: {}", script.display(), e)))?;
+
+    if !output.status.success() {
+        return Err(WarehouseError::Failed(format!(
+            "{} failed with status: {}\nError: {}",
+            script.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| WarehouseError::Failed(format!("Failed to parse output of {}: {}", script.display(), e)))
+}
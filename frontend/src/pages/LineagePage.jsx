@@ -12,7 +12,7 @@ const LineagePage = () => {
   useEffect(() => {
     fetch("http://127.0.0.1:3000/models")
       .then((response) => response.json())
-      .then((data) => setModels(data));
+      .then((data) => setModels(data.map((model) => model.name)));
   }, []);
 
   useEffect(() => {